@@ -0,0 +1,146 @@
+//! Benchmarks for the hot paths of lifting, dropping and dereferencing, comparing the `Arc` based
+//! [`ScopedGuard`] with the pinned [`ScopedPinGuard`] and [`ScopedPinGuardLocal`]. Run with
+//! `cargo bench`.
+
+use std::hint::black_box;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use scoped_static::{Scoped, ScopedGuard, ScopedPinGuard, ScopedPinGuardLocal};
+
+const THREADS: usize = 4;
+const CYCLES: u64 = 10_000;
+
+fn create_guard(c: &mut Criterion) {
+    let value = 1u64;
+    let mut group = c.benchmark_group("create_guard");
+    group.bench_function("arc", |b| {
+        b.iter(|| {
+            let guard = unsafe { ScopedGuard::new(black_box(&value)) };
+            black_box(&guard);
+        })
+    });
+    group.bench_function("pin", |b| {
+        b.iter(|| {
+            let guard = unsafe { ScopedPinGuard::new(black_box(&value)) };
+            black_box(&guard);
+        })
+    });
+    group.finish();
+}
+
+fn lift_drop(c: &mut Criterion) {
+    let value = 1u64;
+    let mut group = c.benchmark_group("lift_drop");
+    group.bench_function("arc", |b| {
+        let guard = unsafe { ScopedGuard::new(&value) };
+        b.iter(|| black_box(guard.lift()));
+    });
+    group.bench_function("pin", |b| {
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&value) };
+        let guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        b.iter(|| black_box(guard.lift()));
+    });
+    group.bench_function("local", |b| {
+        let guard_unpinned = unsafe { ScopedPinGuardLocal::new(&value) };
+        let guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        b.iter(|| black_box(guard.lift()));
+    });
+    group.finish();
+}
+
+fn clone_drop(c: &mut Criterion) {
+    let value = 1u64;
+    let mut group = c.benchmark_group("clone_drop");
+    group.bench_function("arc", |b| {
+        let guard = unsafe { ScopedGuard::new(&value) };
+        let lifted = guard.lift();
+        b.iter(|| black_box(lifted.clone()));
+    });
+    group.bench_function("pin", |b| {
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&value) };
+        let guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        let lifted = guard.lift();
+        b.iter(|| black_box(lifted.clone()));
+    });
+    group.finish();
+}
+
+fn deref(c: &mut Criterion) {
+    const HANDLES: usize = 1_000;
+    let values: Vec<u64> = (0..HANDLES as u64).collect();
+    let mut group = c.benchmark_group("deref");
+    group.throughput(Throughput::Elements(HANDLES as u64));
+    group.bench_function("arc", |b| {
+        let guard = unsafe { ScopedGuard::new(values.as_slice()) };
+        let slice = guard.lift();
+        let lifted: Vec<_> = (0..HANDLES)
+            .map(|i| Scoped::get(&slice, i).unwrap())
+            .collect();
+        b.iter(|| black_box(&lifted).iter().map(|value| **value).sum::<u64>());
+    });
+    // What `Scoped` would be if it kept the reference in the `Arc`, which takes a second load
+    group.bench_function("arc_of_ref", |b| {
+        let lifted: Vec<Arc<&u64>> = values.iter().map(Arc::new).collect();
+        b.iter(|| black_box(&lifted).iter().map(|value| **value).sum::<u64>());
+    });
+    group.bench_function("pin", |b| {
+        let guards: Vec<_> = values
+            .iter()
+            .map(|value| unsafe { ScopedPinGuard::new(value) })
+            .collect();
+        let lifted: Vec<_> = guards
+            .iter()
+            .map(|guard| unsafe { Pin::new_unchecked(guard) }.lift())
+            .collect();
+        b.iter(|| black_box(&lifted).iter().map(|value| **value).sum::<u64>());
+    });
+    group.finish();
+}
+
+fn concurrent_lift_drop(c: &mut Criterion) {
+    let value = 1u64;
+    let mut group = c.benchmark_group("concurrent_lift_drop");
+    group.throughput(Throughput::Elements(THREADS as u64 * CYCLES));
+    group.bench_function(BenchmarkId::new("arc", THREADS), |b| {
+        let guard = unsafe { ScopedGuard::new(&value) };
+        b.iter(|| {
+            std::thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(|| {
+                        for _ in 0..CYCLES {
+                            black_box(guard.lift());
+                        }
+                    });
+                }
+            })
+        });
+    });
+    group.bench_function(BenchmarkId::new("pin", THREADS), |b| {
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&value) };
+        let guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        b.iter(|| {
+            std::thread::scope(|s| {
+                for _ in 0..THREADS {
+                    s.spawn(move || {
+                        for _ in 0..CYCLES {
+                            black_box(guard.lift());
+                        }
+                    });
+                }
+            })
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    create_guard,
+    lift_drop,
+    clone_drop,
+    deref,
+    concurrent_lift_drop
+);
+criterion_main!(benches);
@@ -0,0 +1,18 @@
+//! With the `panic_on_leak` feature, dropping a guard while lifted references still exist panics
+//! instead of aborting the whole program.
+//!
+//! Run with `cargo run --example panic_on_leak --features panic_on_leak`.
+use scoped_static::ScopedGuard;
+
+fn main() {
+    let value = Box::new(1.0);
+    let result = std::panic::catch_unwind(|| {
+        let guard = unsafe { ScopedGuard::new(&value) };
+        let lifted = guard.lift();
+        // Panics, since `lifted` still exists
+        std::mem::drop(guard);
+        std::mem::drop(lifted);
+    });
+    assert!(result.is_err());
+    println!("Recovered from dropping a guard while a lifted reference still existed.");
+}
@@ -0,0 +1,25 @@
+//! Lifting references for threads spawned with `std::thread`, without an async runtime.
+//!
+//! Run with `cargo run --example std_thread`.
+use scoped_static::{scope, scoped};
+
+fn main() {
+    let numbers = vec![1, 2, 3, 4];
+
+    // With a guard, which must outlive the spawned threads.
+    let guard = scoped!(&numbers);
+    let handles: Vec<_> = (0..2)
+        .map(|half| guard.spawn(move |lifted| lifted[half * 2..half * 2 + 2].iter().sum::<i32>()))
+        .collect();
+    let sum: i32 = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .sum();
+    println!("Sum with a guard: {sum}");
+
+    // With `scope`, which waits for the detached thread before returning.
+    scope(|s| {
+        let lifted = s.lift(&numbers);
+        std::thread::spawn(move || println!("Max in a detached thread: {:?}", lifted.iter().max()));
+    });
+}
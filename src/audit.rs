@@ -0,0 +1,67 @@
+//! A registry of the guards in the process, for auditing leaks across a whole test suite. Only with
+//! the `audit` feature. Guards are registered on their first lift rather than when created, since the
+//! pinned guards can be created in a const context.
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+static OUTSTANDING: AtomicUsize = AtomicUsize::new(0);
+static LEAKED: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a guard from its first lifted reference until it and all of its lifted references are gone.
+pub(crate) fn register() {
+    OUTSTANDING.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn deregister() {
+    OUTSTANDING.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Counts a guard that was dropped while lifted references still existed.
+pub(crate) fn record_leak() {
+    LEAKED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The number of guards that lifted a reference and are not gone yet, e.g. since they were
+/// forgotten, or since they leaked and a lifted reference still exists. A [`crate::ScopedGuard`]
+/// is gone once it and all of its clones and lifted references are dropped, a pinned guard once it is
+/// dropped.
+///
+/// Only [`crate::ScopedGuard`] and the guards of [`crate::ScopedPin`], e.g. [`crate::ScopedPinGuard`]
+/// and [`crate::ScopedPinGuardMulti`], are counted, and only from their first lift. So a guard that is
+/// forgotten before it ever lifts is not counted, which is harmless since nothing can dangle.
+/// [`crate::ScopedMutGuard`] and [`crate::ScopedPinGuardLocal`] are never counted here, only their
+/// leaks are in [`leaked_scopes`].
+pub fn outstanding_scopes() -> usize {
+    OUTSTANDING.load(Ordering::Relaxed)
+}
+
+/// The number of guards of any type that were dropped while lifted references still existed. This
+/// only grows, and is only observable with the `test` or `panic_on_leak` feature, since otherwise the
+/// process aborts.
+pub fn leaked_scopes() -> usize {
+    LEAKED.load(Ordering::Relaxed)
+}
+
+/// Panics if any counted guard is outstanding or any guard ever leaked. See [`outstanding_scopes`] and
+/// [`leaked_scopes`]. e.g. for the teardown of a test suite, once all the tests are done, since the
+/// guards of tests that are still running are outstanding.
+///
+/// ```rust
+/// use scoped_static::{assert_no_outstanding_scopes, scoped};
+///
+/// let value = 1.0;
+/// {
+///     let guard = scoped!(&value);
+///     assert_eq!(*guard.lift(), 1.0);
+/// }
+/// assert_no_outstanding_scopes();
+/// ```
+#[track_caller]
+pub fn assert_no_outstanding_scopes() {
+    let outstanding = outstanding_scopes();
+    let leaked = leaked_scopes();
+    assert!(
+        outstanding == 0 && leaked == 0,
+        "{outstanding} guards are outstanding and {leaked} guards leaked"
+    );
+}
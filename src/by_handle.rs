@@ -0,0 +1,121 @@
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+#[cfg(feature = "alloc")]
+use crate::Scoped;
+use crate::ScopedPin;
+
+/// Compares a lifted reference by the guard it was derived from, instead of by the underlying value
+/// like the lifted references themselves do. e.g. so a `BTreeSet` or `HashSet` of observers keeps
+/// the handles of different guards apart, even if their values are equal.
+///
+/// Like [`Scoped::ptr_eq`] and [`ScopedPin::ptr_eq`], the handles derived from the same guard,
+/// e.g. clones, are equal regardless of their values. The order between guards is by address, so it
+/// is arbitrary but stable while they exist.
+///
+/// ```rust
+/// use std::collections::BTreeSet;
+/// use scoped_static::{scoped, ByHandle};
+///
+/// let first = 1.0;
+/// let second = 1.0;
+/// let first_guard = scoped!(&first);
+/// let second_guard = scoped!(&second);
+/// let mut observers = BTreeSet::new();
+/// observers.insert(ByHandle(first_guard.lift()));
+/// observers.insert(ByHandle(second_guard.lift()));
+/// assert_eq!(observers.len(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ByHandle<P>(pub P);
+
+macro_rules! impl_by_handle {
+    ($(#[$attr:meta])* $handle:ident<T $(: ?$unsized:ident)?>) => {
+        $(#[$attr])*
+        impl<T: 'static $(+ ?$unsized)?> PartialEq for ByHandle<$handle<T>> {
+            fn eq(&self, other: &Self) -> bool {
+                $handle::guard_ptr(&self.0) == $handle::guard_ptr(&other.0)
+            }
+        }
+
+        $(#[$attr])*
+        impl<T: 'static $(+ ?$unsized)?> Eq for ByHandle<$handle<T>> {}
+
+        $(#[$attr])*
+        impl<T: 'static $(+ ?$unsized)?> PartialOrd for ByHandle<$handle<T>> {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        $(#[$attr])*
+        impl<T: 'static $(+ ?$unsized)?> Ord for ByHandle<$handle<T>> {
+            fn cmp(&self, other: &Self) -> Ordering {
+                $handle::guard_ptr(&self.0).cmp(&$handle::guard_ptr(&other.0))
+            }
+        }
+
+        $(#[$attr])*
+        impl<T: 'static $(+ ?$unsized)?> Hash for ByHandle<$handle<T>> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                $handle::guard_ptr(&self.0).hash(state);
+            }
+        }
+    };
+}
+
+impl_by_handle!(#[cfg(feature = "alloc")] Scoped<T: ?Sized>);
+impl_by_handle!(ScopedPin<T>);
+
+#[cfg(test)]
+// The order only depends on the address of the count, not on the count itself
+#[allow(clippy::mutable_key_type)]
+mod tests {
+    use std::collections::{BTreeSet, HashSet};
+
+    use super::ByHandle;
+    use crate::{Scoped, ScopedGuard, ScopedPinGuard};
+
+    #[test]
+    fn scoped() {
+        let first = Box::new(1.0);
+        let second = Box::new(1.0);
+        let first_guard = unsafe { ScopedGuard::new(&first) };
+        let second_guard = unsafe { ScopedGuard::new(&second) };
+        let first_lifted = first_guard.lift();
+        let second_lifted = second_guard.lift();
+        assert_eq!(first_lifted, second_lifted, "the values are equal");
+
+        let mut set = BTreeSet::new();
+        assert!(set.insert(ByHandle(first_lifted.clone())));
+        assert!(set.insert(ByHandle(second_lifted.clone())));
+        assert!(
+            !set.insert(ByHandle(first_lifted.clone())),
+            "a clone has the same guard"
+        );
+        assert_eq!(set.len(), 2);
+        assert!(
+            set.iter()
+                .any(|handle| Scoped::ptr_eq(&handle.0, &second_lifted))
+        );
+        let hashed: HashSet<_> = set.iter().cloned().collect();
+        assert_eq!(hashed.len(), 2);
+        std::mem::drop((set, hashed, first_lifted, second_lifted));
+        std::mem::drop((first_guard, second_guard));
+    }
+
+    #[test]
+    fn scoped_pin() {
+        let first = Box::new(1.0);
+        let second = Box::new(1.0);
+        let first_guard = std::pin::pin!(unsafe { ScopedPinGuard::new(&first) });
+        let second_guard = std::pin::pin!(unsafe { ScopedPinGuard::new(&second) });
+        let mut set = BTreeSet::new();
+        assert!(set.insert(ByHandle(first_guard.as_ref().lift())));
+        assert!(set.insert(ByHandle(second_guard.as_ref().lift())));
+        assert!(!set.insert(ByHandle(first_guard.as_ref().lift())));
+        assert_eq!(set.len(), 2);
+        assert_eq!(first_guard.lifted_count(), 1);
+        std::mem::drop(set);
+    }
+}
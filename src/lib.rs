@@ -1,9 +1,61 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "audit")]
+mod audit;
+mod by_handle;
+#[cfg(feature = "std")]
+mod scope;
+mod lifts;
+mod scoped_local;
 mod scoped_pin;
+#[cfg(feature = "alloc")]
 mod scoped;
+#[cfg(feature = "alloc")]
+mod scoped_mut;
+#[cfg(feature = "alloc")]
+mod scoped_tuple;
+#[cfg(feature = "alloc")]
+mod scoped_with;
+mod spawn_safe;
+mod sync;
 mod utils;
 
-pub use scoped_pin::{ScopedPin, ScopedPinGuard};
-pub use scoped::{Scoped, ScopedGuard};
+#[cfg(feature = "audit")]
+#[cfg_attr(docsrs, doc(cfg(feature = "audit")))]
+pub use audit::{assert_no_outstanding_scopes, leaked_scopes, outstanding_scopes};
+pub use by_handle::ByHandle;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use scope::{SafeScope, Scope, scope};
+pub use scoped_local::{ScopedLocal, ScopedPinGuardLocal};
+pub use scoped_pin::{
+    ScopeToken, ScopedArrayGuard, ScopedPin, ScopedPinGuard, ScopedPinGuardMulti,
+    ScopedPinGuardOwned, ScopedPinGuardPinned, ScopedPinPinned,
+};
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use scoped::{Scoped, ScopedGuard, WeakScoped};
+#[cfg(feature = "notify")]
+#[cfg_attr(docsrs, doc(cfg(feature = "notify")))]
+pub use scoped::ScopedGuardBuilder;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use scoped_mut::{ScopedMut, ScopedMutGuard};
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use scoped_tuple::{RefTuple, ScopedTuple, StaticRefTuple};
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use scoped_with::{Family, ScopedWith, ScopedWithGuard};
+pub use spawn_safe::{SpawnSafe, assert_spawn_safe};
+#[cfg(feature = "notify")]
+#[cfg_attr(docsrs, doc(cfg(feature = "notify")))]
+pub use sync::Notifier;
+pub use utils::{
+    LeakError, MAX_PRE_ABORT, NewError, has_poisoned, register_pre_abort, set_leak_handler,
+};
@@ -0,0 +1,184 @@
+//! Where the outstanding lifted references were created, for diagnosing leaks. Only recorded with the
+//! `lift_backtrace` feature. With the `audit` feature it also registers the guard on the first lift.
+//! Otherwise this is empty.
+
+use core::fmt;
+
+/// The records of all guards in the process, by the key of their guard and then in the order they
+/// were created. They are kept outside of the guards, so a lifted reference can remove its own record
+/// without reading its guard, which may already be gone. e.g. a pinned guard that is moved and then
+/// dropped while a lifted reference still exists, which then drops while unwinding from the leak.
+#[cfg(feature = "lift_backtrace")]
+static LIFTS: std::sync::Mutex<std::collections::BTreeMap<(u64, u64), Lift>> =
+    std::sync::Mutex::new(std::collections::BTreeMap::new());
+/// The next guard key or lift id. Never `0`, which marks a guard without a key yet.
+#[cfg(feature = "lift_backtrace")]
+static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+/// Identifies a lifted reference in [`Lifts`]. Empty without the `lift_backtrace` feature.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LiftId {
+    #[cfg(feature = "lift_backtrace")]
+    key: u64,
+    #[cfg(feature = "lift_backtrace")]
+    id: u64,
+}
+
+/// The lifted references of a guard that currently exist, with the location and backtrace of the
+/// `lift` or `clone` that created each of them. Printed when the guard is dropped while any of them
+/// still exist.
+#[derive(Debug)]
+pub(crate) struct Lifts {
+    /// The key of the records of this guard, assigned on the first lift, so [`Lifts::new`] stays const.
+    #[cfg(feature = "lift_backtrace")]
+    key: std::sync::atomic::AtomicU64,
+    /// Whether the guard is counted in [`crate::outstanding_scopes`].
+    #[cfg(feature = "audit")]
+    registered: core::sync::atomic::AtomicBool,
+}
+
+#[cfg(feature = "lift_backtrace")]
+#[derive(Debug)]
+struct Lift {
+    location: &'static core::panic::Location<'static>,
+    /// Shared, so it can be handed out while the lifted reference still exists.
+    backtrace: std::sync::Arc<std::backtrace::Backtrace>,
+}
+
+impl Default for Lifts {
+    fn default() -> Self {
+        Lifts::new()
+    }
+}
+
+impl Lifts {
+    pub(crate) const fn new() -> Self {
+        Lifts {
+            #[cfg(feature = "lift_backtrace")]
+            key: std::sync::atomic::AtomicU64::new(0),
+            #[cfg(feature = "audit")]
+            registered: core::sync::atomic::AtomicBool::new(false),
+        }
+    }
+
+    /// Records a new lifted reference created by the caller.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    #[inline]
+    pub(crate) fn record(&self) -> LiftId {
+        #[cfg(feature = "audit")]
+        if !self.registered.load(core::sync::atomic::Ordering::Relaxed)
+            && !self.registered.swap(true, core::sync::atomic::Ordering::Relaxed)
+        {
+            crate::audit::register();
+        }
+        #[cfg(feature = "lift_backtrace")]
+        {
+            let lift = Lift {
+                location: core::panic::Location::caller(),
+                backtrace: std::sync::Arc::new(std::backtrace::Backtrace::capture()),
+            };
+            let key = self.key();
+            let id = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            lock().insert((key, id), lift);
+            LiftId { key, id }
+        }
+        #[cfg(not(feature = "lift_backtrace"))]
+        LiftId {}
+    }
+
+    /// Forgets a lifted reference that is dropped. Only reads `lift`, never this, so it is still
+    /// sound if the guard is already gone, in which case the record is already gone too.
+    #[inline]
+    pub(crate) fn remove(&self, _lift: LiftId) {
+        #[cfg(feature = "lift_backtrace")]
+        lock().remove(&(_lift.key, _lift.id));
+    }
+
+    /// The backtraces of the lifted references that currently exist, in the order they were created.
+    #[cfg(feature = "lift_backtrace")]
+    pub(crate) fn backtraces(&self) -> Vec<std::sync::Arc<std::backtrace::Backtrace>> {
+        let lifts = lock();
+        Self::of(&lifts, self.key.load(std::sync::atomic::Ordering::Relaxed))
+            .map(|lift| std::sync::Arc::clone(&lift.backtrace))
+            .collect()
+    }
+
+    #[cfg(feature = "lift_backtrace")]
+    fn key(&self) -> u64 {
+        use std::sync::atomic::Ordering::Relaxed;
+        let key = self.key.load(Relaxed);
+        if key != 0 {
+            return key;
+        }
+        let new = NEXT.fetch_add(1, Relaxed);
+        match self.key.compare_exchange(0, new, Relaxed, Relaxed) {
+            Ok(_) => new,
+            Err(key) => key,
+        }
+    }
+
+    /// The records of the guard with `key`, which has none if it is `0`.
+    #[cfg(feature = "lift_backtrace")]
+    fn of(
+        lifts: &std::collections::BTreeMap<(u64, u64), Lift>,
+        key: u64,
+    ) -> impl Iterator<Item = &Lift> {
+        lifts
+            .range((key, 0)..=(key, u64::MAX))
+            .map(|(_, lift)| lift)
+    }
+}
+
+#[cfg(feature = "lift_backtrace")]
+fn lock() -> std::sync::MutexGuard<'static, std::collections::BTreeMap<(u64, u64), Lift>> {
+    LIFTS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(any(feature = "audit", feature = "lift_backtrace"))]
+impl Drop for Lifts {
+    fn drop(&mut self) {
+        #[cfg(feature = "audit")]
+        if *self.registered.get_mut() {
+            crate::audit::deregister();
+        }
+        // The records of lifted references that were forgotten, or that still exist after a leak
+        #[cfg(feature = "lift_backtrace")]
+        {
+            let key = *self.key.get_mut();
+            if key != 0 {
+                let mut lifts = lock();
+                let ids: Vec<_> = lifts
+                    .range((key, 0)..=(key, u64::MAX))
+                    .map(|(id, _)| *id)
+                    .collect();
+                for id in ids {
+                    lifts.remove(&id);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for Lifts {
+    fn fmt(&self, _f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        #[cfg(feature = "lift_backtrace")]
+        let lifts = lock();
+        #[cfg(feature = "lift_backtrace")]
+        for lift in Self::of(&lifts, self.key.load(std::sync::atomic::Ordering::Relaxed)) {
+            write!(_f, "\nLifted reference created at {}", lift.location)?;
+            match lift.backtrace.status() {
+                std::backtrace::BacktraceStatus::Captured => {
+                    write!(_f, ":\n{}", lift.backtrace)?
+                }
+                std::backtrace::BacktraceStatus::Disabled => writeln!(
+                    _f,
+                    " (re-run with `RUST_BACKTRACE=1` to see its backtrace)"
+                )?,
+                _ => writeln!(_f)?,
+            }
+        }
+        Ok(())
+    }
+}
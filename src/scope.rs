@@ -0,0 +1,226 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{Scoped, ScopedGuard};
+
+/// Creates a [`Scope`] for lifting references with lifetime `'a` to `'static`. Before returning,
+/// `scope` blocks until every [`Scoped`] lifted through the [`Scope`] has been dropped. Thus, unlike
+/// [`ScopedGuard`], there is no guard that could be leaked or dropped early, so this is entirely safe.
+/// Modeled on [`std::thread::scope`].
+///
+/// ```rust
+/// let value = vec![1, 2, 3];
+/// let sum = scoped_static::scope(|s| {
+///     let lifted = s.lift(&value);
+///     std::thread::spawn(move || lifted.iter().sum::<i32>())
+///         .join()
+///         .unwrap()
+/// });
+/// assert_eq!(sum, 6);
+/// ```
+///
+/// If `f` panics, the panic is resumed once every [`Scoped`] has been dropped.
+///
+/// Note: Waiting uses exponential backoff like [`ScopedGuard::join`]. A [`Scoped`] that is only
+/// dropped after `scope` returns, e.g. by the caller waiting for a thread that holds it, deadlocks.
+pub fn scope<'a, F, R>(f: F) -> R
+where
+    F: for<'s> FnOnce(&'s Scope<'a>) -> R,
+{
+    let scope = Scope {
+        guard: unsafe { ScopedGuard::new(&()) },
+        _invariant: PhantomData,
+    };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scope)));
+    scope.guard.join();
+    match result {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
+
+/// A scope to lift references with lifetime `'a` to `'static` in. See [`scope`].
+///
+/// Only references that outlive the call to [`scope`] can be lifted, so a value local to the
+/// closure cannot be:
+///
+/// ```rust,compile_fail
+/// scoped_static::scope(|s| {
+///     let value = 1.0;
+///     let lifted = s.lift(&value);
+///     std::thread::spawn(move || println!("{}", *lifted));
+/// });
+/// ```
+#[derive(Debug)]
+pub struct Scope<'a> {
+    // Only used for its count. It is owned by `scope`, so it cannot be leaked.
+    guard: ScopedGuard<'a, ()>,
+    // Invariant, so `Scope<'a>` cannot be shortened to lift references local to the closure.
+    _invariant: PhantomData<&'a mut &'a ()>,
+}
+
+impl<'a> Scope<'a> {
+    /// Lifts `value` with lifetime `'a` into `'static`. The enclosing [`scope`] does not return until
+    /// the returned [`Scoped`] and all of its clones have been dropped.
+    pub fn lift<T: 'static + ?Sized>(&self, value: &'a T) -> Scoped<T> {
+        self.guard.lift_other(value)
+    }
+
+    /// The number of [`Scoped`] lifted through this scope that currently exist.
+    pub fn lifted_count(&self) -> usize {
+        self.guard.lifted_count()
+    }
+}
+
+/// A safe [`ScopedGuard`] for a single value, e.g. for libraries that wrap this crate and want to
+/// lift without `unsafe` or the [`crate::scoped`] macro. It is only handed out by reference inside
+/// [`SafeScope::with`], which owns the guard, so it cannot be leaked or dropped early.
+///
+/// Unlike [`scope`], this does not wait for the lifted references. Like the guards of the macros, it
+/// aborts if any [`Scoped`] still exists when [`SafeScope::with`] returns.
+///
+/// The scope cannot escape the closure:
+///
+/// ```rust,compile_fail
+/// use scoped_static::SafeScope;
+///
+/// let value = 1.0;
+/// let escaped = SafeScope::with(&value, |s| s);
+/// ```
+#[derive(Debug)]
+pub struct SafeScope<'a, T: 'static + ?Sized> {
+    guard: ScopedGuard<'a, T>,
+}
+
+impl<'a, T: 'static + ?Sized> SafeScope<'a, T> {
+    /// Creates a [`SafeScope`] for `value` and calls `f` with it.
+    ///
+    /// ```rust
+    /// use scoped_static::SafeScope;
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let sum = SafeScope::with(&value, |s| {
+    ///     let lifted = s.lift();
+    ///     std::thread::spawn(move || lifted.iter().sum::<i32>())
+    ///         .join()
+    ///         .unwrap()
+    /// });
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn with<F, R>(value: &'a T, f: F) -> R
+    where
+        F: for<'s> FnOnce(&'s SafeScope<'a, T>) -> R,
+    {
+        // `with` owns the guard and drops it before returning, even if `f` panics, so it cannot be
+        // leaked. `f` only gets a shared reference, which cannot clone or replace it.
+        let scope = SafeScope {
+            guard: unsafe { ScopedGuard::new(value) },
+        };
+        f(&scope)
+    }
+
+    /// Lifts the value into `'static`. The [`Scoped`] must be dropped before [`SafeScope::with`]
+    /// returns, otherwise it aborts.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn lift(&self) -> Scoped<T> {
+        self.guard.lift()
+    }
+
+    /// The number of [`Scoped`] lifted through this scope that currently exist.
+    pub fn lifted_count(&self) -> usize {
+        self.guard.lifted_count()
+    }
+}
+
+impl<'a, T: 'static + ?Sized> Deref for SafeScope<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    use super::{SafeScope, scope};
+
+    #[test]
+    fn joined_thread() {
+        let concrete_value = Box::new(1.0);
+        let name = String::from("one");
+        let result = scope(|s| {
+            let lifted = s.lift(&*concrete_value);
+            let lifted_name = s.lift(name.as_str());
+            assert_eq!(s.lifted_count(), 2);
+            std::thread::spawn(move || format!("{lifted_name} {}", *lifted))
+                .join()
+                .unwrap()
+        });
+        assert_eq!(result, "one 1");
+    }
+
+    #[test]
+    fn waits_for_detached_thread() {
+        let concrete_value = Box::new(1.0);
+        let dropped = Arc::new(AtomicBool::new(false));
+        scope(|s| {
+            let lifted = s.lift(&*concrete_value);
+            let dropped = dropped.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                assert_eq!(*lifted, 1.0);
+                dropped.store(true, Ordering::SeqCst);
+                std::mem::drop(lifted);
+            });
+        });
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn panic_waits_and_resumes() {
+        let concrete_value = Box::new(1.0);
+        let result = std::panic::catch_unwind(|| {
+            scope(|s| {
+                let lifted = s.lift(&*concrete_value);
+                std::thread::spawn(move || {
+                    std::thread::sleep(Duration::from_millis(10));
+                    assert_eq!(*lifted, 1.0);
+                });
+                panic!("closure panicked");
+            })
+        });
+        let payload = result.expect_err("expected the panic of the closure to be resumed");
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"closure panicked"));
+    }
+
+    #[test]
+    fn safe_scope() {
+        let concrete_value = Box::new(1.0);
+        let result = SafeScope::with(&*concrete_value, |s| {
+            assert_eq!(**s, 1.0);
+            let lifted = s.lift();
+            assert_eq!(s.lifted_count(), 1);
+            std::thread::spawn(move || *lifted * 2.0).join().unwrap()
+        });
+        assert_eq!(result, 2.0);
+    }
+
+    #[test]
+    fn safe_scope_dangling() {
+        let concrete_value = Box::new(1.0);
+        let mut escaped = None;
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            SafeScope::with(&*concrete_value, |s| escaped = Some(s.lift()));
+        }));
+        assert!(
+            result.is_err(),
+            "expected panic when a Scoped outlives its SafeScope"
+        );
+        std::mem::forget(escaped);
+    }
+}
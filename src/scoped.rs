@@ -1,7 +1,29 @@
-use std::sync::Arc;
-use std::{marker::PhantomData, mem, ops::Deref};
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use core::any::Any;
+use core::borrow::Borrow;
+use core::cell::{Ref, RefCell, RefMut};
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::panic::{RefUnwindSafe, UnwindSafe};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic;
+use core::{
+    marker::PhantomData,
+    mem::{self, ManuallyDrop},
+    ops::Deref,
+};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
-use crate::utils;
+use crate::lifts::{LiftId, Lifts};
+use crate::sync::{AtomicUsize, Drained};
+#[cfg(feature = "notify")]
+use crate::sync::Notifier;
+use crate::utils::{self, NewError};
+use crate::ScopedPin;
 
 /// A safe way to create a [`ScopedGuard`].
 /// ```rust
@@ -27,142 +49,3007 @@ use crate::utils;
 #[macro_export]
 macro_rules! scoped {
     ($ref_value:expr) => {
-        &mut unsafe { $crate::ScopedGuard::new($ref_value) }
+        &mut {
+            let ref_value = $ref_value;
+            unsafe { $crate::ScopedGuard::new(ref_value) }
+        }
+    };
+}
+
+/// A safe way to create multiple [`ScopedGuard`] at once. Expands to a tuple with one guard per
+/// reference, like calling [`scoped`] for each. Each guard has its own count, so they can be dropped
+/// in any order.
+/// ```rust
+/// use scoped_static::scoped_all;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let number = Box::new(1.0);
+///     let name = String::from("one");
+///     let (number_guard, name_guard) = scoped_all!(&number, &name);
+///     let number = number_guard.lift();
+///     let name = name_guard.lift();
+///     tokio::spawn(async move {
+///         assert_eq!(**number, 1.0);
+///         assert_eq!(*name, "one");
+///     })
+///     .await
+///     .unwrap();
+/// }
+/// ```
+#[macro_export]
+macro_rules! scoped_all {
+    ($($ref_value:expr),+ $(,)?) => {
+        ($($crate::scoped!($ref_value),)+)
+    };
+}
+
+/// A safe way to lift a reference once, for when only the [`Scoped`] is needed. Binds the lifted
+/// reference to the given identifier, like [`scoped`] followed by [`ScopedGuard::lift`]. The guard
+/// is kept in a binding that cannot be named, so it cannot be moved or forgotten, and is dropped at
+/// the end of the enclosing scope after the lifted reference, with the same checks as any guard.
+/// ```rust
+/// use scoped_static::scoped_lift;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let concrete_value = Box::new(1.0);
+///     scoped_lift!(lifted, &concrete_value);
+///     tokio::spawn(async move {
+///         assert_eq!(**lifted, 1.0);
+///     })
+///     .await
+///     .unwrap();
+///     // The guard is dropped here
+/// }
+/// ```
+#[macro_export]
+macro_rules! scoped_lift {
+    ($lifted_ident:ident, $ref_value:expr) => {
+        let guard = {
+            let ref_value = $ref_value;
+            unsafe { $crate::ScopedGuard::new(ref_value) }
+        };
+        let $lifted_ident = guard.lift();
     };
 }
 
-/// A reference with lifetime `'a` that can be lifted to a reference with a `'static` lifetime ([`Scoped`]).
-/// Runtime checks are used to ensure that no derived [`Scoped`] exists when this [`ScopedGuard`] is
-/// dropped.
-///
-/// ```rust
-/// use scoped_static::ScopedGuard;
-///
-/// #[tokio::main]
-/// async fn main() {
-///     let concrete_value = Box::new(1.0);
-///     let ref_value = &concrete_value;
-///     let guard = unsafe { ScopedGuard::new(ref_value) };
-///     let lifted = guard.lift();
-///     tokio::spawn(async move {
-///         // Lifted is 'static so it can be moved into this closure that needs 'static
-///         let value = **lifted + 1.0;
-///         assert_eq!(value, 2.0);
-///         // `lifted` is dropped here
-///     })
-///     .await
-///     .unwrap();
-///    // `guard` is dropped here
-/// }
-/// ```
-///
-/// If a [`ScopedGuard`] is dropped while any derived [`Scoped`] exist, then it will abort the whole
-/// program (instead of panic). This is because [`Scoped`] could exist on another thread and be unaffected
-/// by the panic or the panic could be recovered from. This could lead to undefined behavior.
-///
-/// Unlike [`crate::ScopedPinGuard`] this uses boxing internally. Thus it is slightly less efficient, but it can be moved.
-///
-/// UNDEFINED BEHAVIOR: It may cause undefined behavior to leak/forget this value. Since
-/// the `Drop` code must run to prevent undefined behavior.
-/// e.g. [`std::mem::forget`], [`std::mem::ManuallyDrop`], or Rc cycles, etc.
-///
-/// See [`scoped`] macro for a safe way to create.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct ScopedGuard<'a, T: 'static> {
-    data: Arc<&'static T>,
-    _scope: PhantomData<&'a ()>,
-}
+/// A reference with lifetime `'a` that can be lifted to a reference with a `'static` lifetime ([`Scoped`]).
+/// Runtime checks are used to ensure that no derived [`Scoped`] exists when this [`ScopedGuard`] is
+/// dropped.
+///
+/// ```rust
+/// use scoped_static::ScopedGuard;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let concrete_value = Box::new(1.0);
+///     let ref_value = &concrete_value;
+///     let guard = unsafe { ScopedGuard::new(ref_value) };
+///     let lifted = guard.lift();
+///     tokio::spawn(async move {
+///         // Lifted is 'static so it can be moved into this closure that needs 'static
+///         let value = **lifted + 1.0;
+///         assert_eq!(value, 2.0);
+///         // `lifted` is dropped here
+///     })
+///     .await
+///     .unwrap();
+///    // `guard` is dropped here
+/// }
+/// ```
+///
+/// If a [`ScopedGuard`] is dropped while any derived [`Scoped`] exist, then it will abort the whole
+/// program (instead of panic). This is because [`Scoped`] could exist on another thread and be unaffected
+/// by the panic or the panic could be recovered from. This could lead to undefined behavior.
+///
+/// Unlike [`crate::ScopedPinGuard`] this uses boxing internally. Thus it is slightly less efficient, but it can be moved.
+///
+/// The count cannot be stored inline without pinning. A derived [`Scoped`] is `'static`, so it
+/// keeps pointing at the count after the borrow used for lifting ends, and a guard that is only
+/// `!Unpin` could still be moved in safe code, e.g. returned or swapped with [`core::mem::swap`].
+/// The handles would then count on stale memory. For allocation free lifts that are still `Send`,
+/// pin the guard to the stack with [`crate::scoped_pin`]:
+///
+/// ```rust
+/// use scoped_static::scoped_pin;
+///
+/// let value = vec![1, 2, 3];
+/// scoped_pin!(guard, &value);
+/// let lifted = guard.lift();
+/// let sum = std::thread::spawn(move || lifted.iter().sum::<i32>())
+///     .join()
+///     .unwrap();
+/// assert_eq!(sum, 6);
+/// ```
+///
+/// UNDEFINED BEHAVIOR: It may cause undefined behavior to leak/forget this value. Since
+/// the `Drop` code must run to prevent undefined behavior.
+/// e.g. [`std::mem::forget`], [`std::mem::ManuallyDrop`], or Rc cycles, etc.
+///
+/// A [`ScopedGuard`] can be cloned with [`ScopedGuard::clone_guard`] to hand a guard to several code
+/// paths. All clones share one count, and only dropping the last clone while derived [`Scoped`] exist
+/// aborts.
+///
+/// Like `&T`, a [`ScopedGuard`] and the derived [`Scoped`] are `Send` and `Sync` exactly when `T` is
+/// `Sync`. The count itself is atomic, so it may be observed from any thread, but the value is only
+/// shared with other threads if it is safe to.
+///
+/// A guard is reusable, e.g. to lift, spawn and join in a loop. Only the count at the time of the
+/// drop matters, so it may go back to zero and up again any number of times.
+///
+/// The type of the value itself must be `'static`, only the reference to it is lifted. A value that
+/// borrows, e.g. `Foo<'b>`, cannot be lifted, since a lifted `Foo<'static>` would allow copying
+/// its borrows out, e.g. into a `static`, where they outlive the guard. Lift what it borrows instead,
+/// e.g. with [`ScopedGuard::new_tuple`], or use a [`crate::ScopedWithGuard`]:
+///
+/// ```rust,compile_fail
+/// use scoped_static::scoped;
+///
+/// struct Foo<'b> {
+///     name: &'b str,
+/// }
+///
+/// let name = String::from("one");
+/// let foo = Foo { name: &name };
+/// let guard = scoped!(&foo);
+/// ```
+///
+/// A reference out of a lock guard, e.g. a [`std::sync::MutexGuard`] or
+/// [`std::sync::RwLockReadGuard`], is lifted like any other. The guard borrows the lock guard, so the
+/// lock is held until after the guard is dropped, and thus after every derived [`Scoped`] is gone.
+/// If one escapes, the guard aborts before the lock is released. As for any `&T`, the derived
+/// [`Scoped`] can only be sent to other threads if `T` is `Sync`, which the lock does not change:
+///
+/// ```rust
+/// use std::sync::Mutex;
+/// use scoped_static::scoped;
+///
+/// let values = Mutex::new(vec![1, 2, 3]);
+/// let locked = values.lock().unwrap();
+/// let guard = scoped!(&*locked);
+/// let lifted = guard.lift();
+/// let sum = std::thread::spawn(move || lifted.iter().sum::<i32>())
+///     .join()
+///     .unwrap();
+/// assert_eq!(sum, 6);
+/// ```
+///
+/// Releasing the lock while the guard exists does not compile:
+///
+/// ```rust,compile_fail
+/// use std::sync::Mutex;
+/// use scoped_static::ScopedGuard;
+///
+/// let values = Mutex::new(vec![1, 2, 3]);
+/// let locked = values.lock().unwrap();
+/// let guard = unsafe { ScopedGuard::new(&*locked) };
+/// drop(locked);
+/// let lifted = guard.lift();
+/// ```
+///
+/// See [`scoped`] macro for a safe way to create.
+pub struct ScopedGuard<'a, T: 'static + ?Sized> {
+    // A raw pointer rather than `&'static T`, since a reference field would be asserted live for the
+    // whole of `drop`, while the drop may free the value when it is stored alongside the count.
+    value: NonNull<T>,
+    // Only taken in `drop`
+    data: ManuallyDrop<Arc<Shared>>,
+    _scope: PhantomData<&'a ()>,
+}
+
+// SAFETY: The guard only hands out shared references to `T`, like `&T` does.
+unsafe impl<'a, T: 'static + Sync + ?Sized> Send for ScopedGuard<'a, T> {}
+unsafe impl<'a, T: 'static + Sync + ?Sized> Sync for ScopedGuard<'a, T> {}
+
+impl<'a, T: 'static + ?Sized> ScopedGuard<'a, T> {
+    /// Creates a new [`ScopedGuard`]. See [`scoped`] for a safe way to create.
+    ///
+    /// # Safety
+    ///
+    /// The returned guard must not be leaked/forgotten. See [`ScopedGuard`].
+    pub unsafe fn new(value: &'a T) -> Self {
+        ScopedGuard {
+            value: NonNull::from(value),
+            data: ManuallyDrop::new(Arc::new(Shared::guarded())),
+            _scope: PhantomData,
+        }
+    }
+
+    /// Like [`ScopedGuard::new`], but in debug builds every access through a derived [`Scoped`]
+    /// first checks that the guard was not dropped while it existed, and panics if so. This gives a
+    /// loud failure for a use after the guard's drop was recovered from, e.g. with the `panic_on_leak`
+    /// feature, instead of reading memory that may already be reused. A guard that is leaked/forgotten
+    /// never runs any code, so that misuse can still not be detected. In release builds this is the
+    /// same as [`ScopedGuard::new`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ScopedGuard::new`].
+    pub unsafe fn new_checked(value: &'a T) -> Self {
+        ScopedGuard {
+            value: NonNull::from(value),
+            data: ManuallyDrop::new(Arc::new(Shared {
+                #[cfg(debug_assertions)]
+                checked: true,
+                ..Shared::guarded()
+            })),
+            _scope: PhantomData,
+        }
+    }
+
+    /// Like [`ScopedGuard::new`], but from a raw pointer, e.g. one obtained over FFI, which is
+    /// checked to be non-null and aligned before any reference to it is created. Returns a
+    /// [`NewError`] instead of a guard over a bogus reference.
+    ///
+    /// This takes a pointer rather than a reference, since a null or misaligned `&T` is already
+    /// undefined behavior when it is created, before it could be checked.
+    ///
+    /// ```rust
+    /// use scoped_static::{NewError, ScopedGuard};
+    ///
+    /// let value = 1u32;
+    /// let guard = unsafe { ScopedGuard::try_from_ptr(&value as *const u32) }.unwrap();
+    /// assert_eq!(*guard.lift(), 1);
+    /// let null = unsafe { ScopedGuard::<u32>::try_from_ptr(std::ptr::null()) };
+    /// assert_eq!(null.unwrap_err(), NewError::Null);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ScopedGuard::new`]. Additionally a non-null and aligned `ptr` must point to a valid
+    /// `T` that is not mutated for `'a`, like a `&'a T`.
+    pub unsafe fn try_from_ptr(ptr: *const T) -> Result<Self, NewError>
+    where
+        T: Sized,
+    {
+        if ptr.is_null() {
+            return Err(NewError::Null);
+        }
+        if !ptr.is_aligned() {
+            return Err(NewError::Misaligned {
+                addr: ptr.addr(),
+                align: mem::align_of::<T>(),
+            });
+        }
+        // SAFETY: Checked above, and valid for `'a` by the contract
+        Ok(unsafe { ScopedGuard::new(&*ptr) })
+    }
+
+    /// Creates a new [`ScopedGuard`] over `value`, which is stored alongside the count so it lives
+    /// as long as the guard.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ScopedGuard::new`]. Additionally `T` must not have drop glue.
+    pub(crate) unsafe fn new_stored(value: T) -> Self
+    where
+        T: Sized,
+    {
+        debug_assert!(!mem::needs_drop::<T>());
+        let data = Arc::new(Shared {
+            storage: Some(Storage(Box::new(value))),
+            ..Shared::guarded()
+        });
+        let value = match &data.storage {
+            Some(Storage(value)) => NonNull::from(value.downcast_ref::<T>().unwrap()),
+            None => unreachable!(),
+        };
+        ScopedGuard {
+            // The storage is never moved or freed while the count exists
+            value,
+            data: ManuallyDrop::new(data),
+            _scope: PhantomData,
+        }
+    }
+
+    /// Lifts this reference with lifetime `'a` into `'static` and relies on runtime
+    /// checks to ensure safety.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn lift(&self) -> Scoped<T> {
+        self.lift_ptr(self.value)
+    }
+
+    /// Like [`ScopedGuard::lift`], but returns `None` if `max` or more [`Scoped`] derived from this
+    /// guard already exist. The check and the increment are a single atomic step.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn try_lift(&self, max: usize) -> Option<Scoped<T>> {
+        // Relaxed for the same reasons as in `lift_ptr`
+        self.data
+            .lifted
+            .fetch_update(atomic::Ordering::Relaxed, atomic::Ordering::Relaxed, |count| {
+                (count < max).then_some(count + 1)
+            })
+            .ok()?;
+        Some(self.counted(self.value))
+    }
+
+    /// Returns a factory that lifts this reference each time it is called, for paths that only
+    /// sometimes need a [`Scoped`]. The count is not touched until the factory is called. The
+    /// factory borrows the guard, so it cannot outlive it.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let value = 1;
+    /// let guard = unsafe { ScopedGuard::new(&value) };
+    /// let lift = guard.lift_on_demand();
+    /// assert_eq!(guard.lifted_count(), 0);
+    /// let lifted = lift();
+    /// assert_eq!(guard.lifted_count(), 1);
+    /// # drop(lifted);
+    /// ```
+    pub fn lift_on_demand(&self) -> impl Fn() -> Scoped<T> + '_ {
+        move || self.lift()
+    }
+
+    /// Lifts another reference with lifetime `'a`, which then counts towards this guard like the
+    /// [`Scoped`] returned by [`ScopedGuard::lift`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub(crate) fn lift_other<U: 'static + ?Sized>(&self, value: &'a U) -> Scoped<U> {
+        self.lift_ptr(NonNull::from(value))
+    }
+
+    /// `value` must be valid for `'a`.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    fn lift_ptr<U: 'static + ?Sized>(&self, value: NonNull<U>) -> Scoped<U> {
+        // Relaxed is enough, like `Arc::clone`. The guard cannot be dropped concurrently since it is
+        // borrowed.
+        self.data.lifted.fetch_add(1, atomic::Ordering::Relaxed);
+        self.counted(value)
+    }
+
+    /// Wraps `value` in a [`Scoped`] that takes over a count the caller already added. `value` must
+    /// be valid for `'a`.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    fn counted<U: 'static + ?Sized>(&self, value: NonNull<U>) -> Scoped<U> {
+        Scoped {
+            value,
+            lift: self.data.lifts.record(),
+            data: Arc::clone(&self.data),
+        }
+    }
+
+    /// The number of [`Scoped`] derived from this guard (or its clones) that currently exist. The
+    /// last clone of this guard can only be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.data.lifted.load(atomic::Ordering::Acquire)
+    }
+
+    /// The backtraces of where the [`Scoped`] derived from this guard (or its clones) that currently
+    /// exist were lifted or cloned, in the order they were created. e.g. for a test or debugging tool
+    /// to report who still holds a reference before the guard is dropped, instead of after the abort.
+    ///
+    /// Like in the abort message, the backtraces are only captured if enabled with `RUST_BACKTRACE`
+    /// or `RUST_LIB_BACKTRACE`. Otherwise each is [`BacktraceStatus::Disabled`], but there is still
+    /// one per [`Scoped`]. The backtraces are shared with the [`Scoped`], which is why they are
+    /// returned in an `Arc`.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let value = 1;
+    /// let guard = unsafe { ScopedGuard::new(&value) };
+    /// let lifted = guard.lift();
+    /// for backtrace in guard.outstanding_backtraces() {
+    ///     eprintln!("still lifted at:\n{backtrace}");
+    /// }
+    /// # drop(lifted);
+    /// ```
+    ///
+    /// [`BacktraceStatus::Disabled`]: std::backtrace::BacktraceStatus::Disabled
+    #[cfg(feature = "lift_backtrace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lift_backtrace")))]
+    pub fn outstanding_backtraces(&self) -> Vec<Arc<std::backtrace::Backtrace>> {
+        self.data.lifts.backtraces()
+    }
+
+    /// Points this guard at `new` if no derived [`Scoped`] exist, e.g. to keep a long-lived guard
+    /// across versions of hot-reloaded data. Otherwise `new` is returned back. Clones of this guard
+    /// keep pointing at the value they were created with.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let (first, second) = (1, 2);
+    /// let mut guard = unsafe { ScopedGuard::new(&first) };
+    /// let lifted = guard.lift();
+    /// assert_eq!(guard.replace(&second), Err(&2));
+    /// drop(lifted);
+    /// assert_eq!(guard.replace(&second), Ok(()));
+    /// assert_eq!(*guard.lift(), 2);
+    /// ```
+    pub fn replace(&mut self, new: &'a T) -> Result<(), &'a T> {
+        // No lift can happen concurrently, since this guard is borrowed mutably. Lifts through clones
+        // of this guard may still raise the count, but they point at their own value.
+        if self.lifted_count() != 0 {
+            return Err(new);
+        }
+        self.value = NonNull::from(new);
+        Ok(())
+    }
+
+    /// Drops this guard if no derived [`Scoped`] exist. Otherwise, instead of aborting, the guard is
+    /// returned so the drop can be retried later.
+    pub fn try_unwrap(self) -> Result<(), Self> {
+        if self.lifted_count() != 0 {
+            return Err(self);
+        }
+        Ok(())
+    }
+
+    /// The underlying value for the whole of `'a`, instead of only for the borrow of this guard like
+    /// `Deref`, e.g. to keep it alongside the guard without borrowing it. Returns `None` for a guard
+    /// from [`ScopedGuard::new_tuple`] or [`ScopedGuard::from_arc`], whose value is stored alongside
+    /// the count and freed with it.
+    ///
+    /// Otherwise the value was borrowed for `'a` when the guard was created, or replaced by one that
+    /// was, so it outlives `'a` regardless of the guard. The guard is covariant in `'a`, so this can
+    /// only ever shorten the borrow. The runtime checks only concern the derived [`Scoped`], which
+    /// this does not create.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let guard = unsafe { ScopedGuard::new(&value) };
+    /// let borrowed = guard.as_ref_a().unwrap();
+    /// drop(guard);
+    /// assert_eq!(borrowed.len(), 3);
+    /// ```
+    pub fn as_ref_a(&self) -> Option<&'a T> {
+        if self.data.stores_value() {
+            return None;
+        }
+        // SAFETY: Points at a value borrowed for `'a`, see above
+        Some(unsafe { self.value.as_ref() })
+    }
+
+    /// Drops this guard and returns the original reference with lifetime `'a`, if no derived
+    /// [`Scoped`] exist. Otherwise, instead of aborting, the guard is returned.
+    ///
+    /// The guard is also returned if it stores the value itself, e.g. one created with
+    /// [`ScopedGuard::new_tuple`] or [`ScopedGuard::from_arc`], since then the value does not outlive
+    /// the guard.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// fn process<'a>(guard: ScopedGuard<'a, String>) -> &'a str {
+    ///     let lifted = guard.lift();
+    ///     std::thread::spawn(move || assert_eq!(*lifted, "one"))
+    ///         .join()
+    ///         .unwrap();
+    ///     guard.into_ref().unwrap()
+    /// }
+    ///
+    /// let name = String::from("one");
+    /// assert_eq!(process(unsafe { ScopedGuard::new(&name) }), "one");
+    /// ```
+    pub fn into_ref(self) -> Result<&'a T, Self> {
+        if self.lifted_count() != 0 || self.data.stores_value() {
+            return Err(self);
+        }
+        let value = self.value;
+        mem::drop(self);
+        // SAFETY: The value is borrowed for `'a` and no `Scoped` to it remains
+        Ok(unsafe { value.as_ref() })
+    }
+
+    /// Lifts the reference and spawns a thread with [`std::thread::spawn`] that is handed the
+    /// [`Scoped`]. This guard must not be dropped before the thread is done with it, e.g. by joining
+    /// the returned handle or with [`ScopedGuard::join`].
+    ///
+    /// ```rust
+    /// use scoped_static::scoped;
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let guard = scoped!(&value);
+    /// let handle = guard.spawn(|lifted| lifted.iter().sum::<i32>());
+    /// assert_eq!(handle.join().unwrap(), 6);
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn spawn<F, R>(&self, f: F) -> std::thread::JoinHandle<R>
+    where
+        T: Sync,
+        F: FnOnce(Scoped<T>) -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let lifted = self.lift();
+        std::thread::spawn(move || f(lifted))
+    }
+
+    /// Lifts the reference, hands the [`Scoped`] to `f`, which spawns a thread with it, and joins
+    /// the thread before returning its result. If the thread panicked, the panic is resumed.
+    ///
+    /// Note: Only the spawned thread is waited for. A clone of the [`Scoped`] that `f` or the thread
+    /// stores elsewhere still keeps this guard from being dropped.
+    ///
+    /// ```rust
+    /// use scoped_static::scoped;
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let guard = scoped!(&value);
+    /// let sum = guard.with_lifted(|lifted| std::thread::spawn(move || lifted.iter().sum::<i32>()));
+    /// assert_eq!(sum, 6);
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn with_lifted<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(Scoped<T>) -> std::thread::JoinHandle<R>,
+    {
+        let handle = f(self.lift());
+        handle
+            .join()
+            .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+    }
+
+    /// Like [`ScopedGuard::with_lifted`], but for a tokio task. Awaits the task before returning its
+    /// result. If the task panicked, the panic is resumed.
+    ///
+    /// Note: If the returned future is dropped before completion, the task keeps running. Then this
+    /// guard cannot be dropped until the task is done, e.g. with [`ScopedGuard::join`].
+    ///
+    /// ```rust
+    /// use scoped_static::scoped;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let value = vec![1, 2, 3];
+    ///     let guard = scoped!(&value);
+    ///     let sum = guard
+    ///         .with_lifted_task(|lifted| tokio::spawn(async move { lifted.iter().sum::<i32>() }))
+    ///         .await;
+    ///     assert_eq!(sum, 6);
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn with_lifted_task<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(Scoped<T>) -> tokio::task::JoinHandle<R>,
+    {
+        let handle = f(self.lift());
+        match handle.await {
+            Ok(result) => result,
+            Err(error) if error.is_panic() => std::panic::resume_unwind(error.into_panic()),
+            Err(error) => panic!("task lifting a reference failed: {error}"),
+        }
+    }
+
+    /// Returns `true` if no derived [`Scoped`] exist, i.e. this guard can currently be dropped
+    /// without aborting. Clones of this guard may still lift afterwards.
+    pub fn is_drained(&self) -> bool {
+        self.lifted_count() == 0
+    }
+
+    /// Blocks the current thread until no derived [`Scoped`] exist. With the `notify` feature the
+    /// thread is parked until the last [`Scoped`] is dropped, otherwise it waits with exponential
+    /// backoff.
+    ///
+    /// ```rust
+    /// use scoped_static::scoped;
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let guard = scoped!(&value);
+    /// let lifted = guard.lift();
+    /// std::thread::spawn(move || {
+    ///     std::thread::sleep(std::time::Duration::from_millis(10));
+    ///     assert_eq!(lifted.len(), 3);
+    /// });
+    /// guard.wait_drained();
+    /// assert!(guard.is_drained());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn wait_drained(&self) {
+        self.data.wait_drained(None);
+    }
+
+    /// Blocks the current thread until no derived [`Scoped`] exist, then drops this guard.
+    /// See [`ScopedGuard::wait_drained`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join(self) {
+        self.wait_drained();
+    }
+
+    /// Like [`ScopedGuard::join`], but gives the guard back if derived [`Scoped`] still exist
+    /// after `timeout` has elapsed.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join_timeout(self, timeout: Duration) -> Result<(), Self> {
+        if self.data.wait_drained(Some(Instant::now() + timeout)) {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Like [`ScopedGuard::join`], but calls `poll` until no derived [`Scoped`] exist instead of
+    /// blocking the thread, then drops this guard. e.g. to keep advancing a single-threaded executor
+    /// that runs the tasks holding the [`Scoped`], where blocking its thread would never finish.
+    ///
+    /// `poll` is called at least once unless the guard is already drained. It is responsible for not
+    /// spinning, e.g. by parking until the executor has work.
+    pub fn block_until<F: FnMut()>(self, mut poll: F) {
+        while !self.is_drained() {
+            poll();
+        }
+    }
+
+    /// Like [`ScopedGuard::wait_drained`], but awaits the last derived [`Scoped`] to be dropped
+    /// instead of blocking the thread, so the executor keeps running other tasks. Independent of the
+    /// `notify` feature, the task is woken up when the count drops to zero.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn wait_drained_async(&self) {
+        self.data.wait_drained_async().await;
+    }
+
+    /// Awaits no derived [`Scoped`] to exist, so this guard can be dropped without aborting. e.g. in
+    /// an async shutdown path, without a dedicated blocking thread. Returns `false` if derived
+    /// [`Scoped`] still exist after `timeout` has elapsed, without a timeout it waits until they are
+    /// dropped.
+    ///
+    /// The guard is only borrowed, so the returned future is cancel safe. Dropping it before it
+    /// completes, e.g. when it loses a `tokio::select!`, leaves the guard with the caller.
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let value = vec![1, 2, 3];
+    ///     let guard = unsafe { ScopedGuard::new(&value) };
+    ///     let lifted = guard.lift();
+    ///     tokio::spawn(async move {
+    ///         tokio::time::sleep(Duration::from_millis(10)).await;
+    ///         assert_eq!(lifted.len(), 3);
+    ///     });
+    ///     assert!(!guard.join_async(Some(Duration::ZERO)).await);
+    ///     assert!(guard.join_async(None).await);
+    ///     drop(guard);
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub async fn join_async(&self, timeout: Option<Duration>) -> bool {
+        match timeout {
+            None => {
+                self.wait_drained_async().await;
+                true
+            }
+            Some(timeout) => tokio::time::timeout(timeout, self.wait_drained_async())
+                .await
+                .is_ok(),
+        }
+    }
+}
+
+impl<'a, T: ?Sized> Deref for ScopedGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+/// Formats the underlying value and the number of lifted references, but none of the shared state
+/// behind them, so the output does not depend on the enabled features.
+impl<'a, T: 'static + fmt::Debug + ?Sized> fmt::Debug for ScopedGuard<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedGuard")
+            .field("value", &&**self)
+            .field("lifted_count", &self.lifted_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T: 'static + PartialEq + ?Sized> PartialEq for ScopedGuard<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<'a, T: 'static + Eq + ?Sized> Eq for ScopedGuard<'a, T> {}
+
+impl<'a, T: 'static + PartialOrd + ?Sized> PartialOrd for ScopedGuard<'a, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<'a, T: 'static + Ord + ?Sized> Ord for ScopedGuard<'a, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<'a, T: 'static + Hash + ?Sized> Hash for ScopedGuard<'a, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+impl<'a, T: 'static + ?Sized> ScopedGuard<'a, T> {
+    /// Creates another guard of the same value that shares the count of this one. Only dropping the
+    /// last clone while derived [`Scoped`] exist aborts.
+    ///
+    /// # Safety
+    /// The same as for [`ScopedGuard::new`], the returned guard must not be leaked. This is not
+    /// `Clone`, since a leaked clone keeps the count of guards above zero, so dropping the last other
+    /// guard would end the borrow without aborting, e.g. through a guard from [`crate::scoped`] that
+    /// is otherwise safe to use.
+    pub unsafe fn clone_guard(&self) -> Self {
+        // Relaxed for the same reasons as in `lift`
+        self.data.guards.fetch_add(1, atomic::Ordering::Relaxed);
+        ScopedGuard {
+            value: self.value,
+            data: ManuallyDrop::new(Arc::clone(&self.data)),
+            _scope: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'static + ?Sized> ScopedGuard<'a, T> {
+    /// Creates a child guard of the same value, e.g. for a task tree where each subtree is waited
+    /// for on its own while a supervisor scope outlives all of them. The child is a guard of its own
+    /// with its own count, so it can be checked, joined and dropped independently. It holds a single
+    /// [`Scoped`] of this guard until it and every [`Scoped`] lifted from it have been dropped, so
+    /// this guard aborts if dropped before that.
+    ///
+    /// This is safe, since leaking the child only leaks that count, which makes this guard abort
+    /// instead of ending the borrow.
+    ///
+    /// ```rust
+    /// use scoped_static::scoped;
+    ///
+    /// let value = vec![1, 2, 3];
+    /// let parent = scoped!(&value);
+    /// let child = parent.child();
+    /// let lifted = child.lift();
+    /// assert_eq!(parent.lifted_count(), 1);
+    /// std::thread::spawn(move || assert_eq!(lifted.len(), 3));
+    /// child.join();
+    /// assert!(parent.is_drained());
+    /// ```
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn child(&self) -> ScopedGuard<'a, T> {
+        let parent = Scoped::map(self.lift(), |_| &());
+        ScopedGuard {
+            // Points at the same value, which the parent keeps borrowed while the child's count exists
+            value: self.value,
+            data: ManuallyDrop::new(Arc::new(Shared {
+                parent: Some(Parent {
+                    lifted: ManuallyDrop::new(parent),
+                    released: AtomicBool::new(false),
+                }),
+                ..Shared::guarded()
+            })),
+            _scope: PhantomData,
+        }
+    }
+}
+
+impl<T: 'static + Send + Sync + ?Sized> ScopedGuard<'static, T> {
+    /// Creates a guard that lifts out of an `Arc` instead of a borrow. The derived [`Scoped`] keep
+    /// the `Arc` alive, so unlike the other guards, this one can be dropped, or even leaked, while
+    /// they still exist and never aborts. e.g. to hand out [`Scoped`] from code that already holds an
+    /// `Arc`, and to keep counting and waiting for them like for a borrow.
+    ///
+    /// The `Arc` is released once this guard and every derived [`Scoped`] have been dropped, on
+    /// whichever thread drops the last of them.
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let guard = ScopedGuard::from_arc(Arc::new(vec![1, 2, 3]));
+    /// let lifted = guard.lift();
+    /// drop(guard);
+    /// assert_eq!(lifted.len(), 3);
+    /// ```
+    pub fn from_arc(arc: Arc<T>) -> Self {
+        let value = NonNull::from(&*arc);
+        ScopedGuard {
+            // The `Arc` is never moved out or released while the count exists
+            value,
+            data: ManuallyDrop::new(Arc::new(Shared {
+                owner: Some(Owner {
+                    _owned: Box::new(arc),
+                }),
+                ..Shared::guarded()
+            })),
+            _scope: PhantomData,
+        }
+    }
+
+    /// Creates a guard that owns `source` and lifts the reference that `project` returns into it,
+    /// e.g. an element of a `Vec`. Since the guard owns what it borrows from, the source cannot be
+    /// dropped or moved before the guard. Like for [`ScopedGuard::from_arc`], the derived [`Scoped`]
+    /// keep the source alive, so this guard never aborts.
+    ///
+    /// The source is dropped once this guard and every derived [`Scoped`] have been dropped, on
+    /// whichever thread drops the last of them.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let guard = ScopedGuard::own_with(vec![1, 2, 3], |values| &values[1]);
+    /// let lifted = guard.lift();
+    /// std::thread::spawn(move || assert_eq!(*lifted, 2))
+    ///     .join()
+    ///     .unwrap();
+    /// ```
+    ///
+    /// The reference must borrow from the source, or be `'static`:
+    ///
+    /// ```rust,compile_fail
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// let local = 1;
+    /// let guard = ScopedGuard::own_with(vec![1, 2, 3], |_| &local);
+    /// ```
+    pub fn own_with<S: Send + Sync + 'static>(source: S, project: impl FnOnce(&S) -> &T) -> Self {
+        let data = Arc::new(Shared {
+            owner: Some(Owner {
+                _owned: Box::new(source),
+            }),
+            ..Shared::guarded()
+        });
+        let value = match &data.owner {
+            Some(Owner { _owned: source }) => NonNull::from(project(source.downcast_ref().unwrap())),
+            None => unreachable!(),
+        };
+        ScopedGuard {
+            // The source is never moved or dropped while the count exists
+            value,
+            data: ManuallyDrop::new(data),
+            _scope: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: 'static + ?Sized> Drop for ScopedGuard<'a, T> {
+    fn drop(&mut self) {
+        let data = unsafe { ManuallyDrop::take(&mut self.data) };
+        // AcqRel so the last clone observes the lifts of all other clones. Acquire synchronizes with
+        // the Release decrement of every dropped `Scoped`, so all their uses of the value happen
+        // before the borrow ends. Once the count is `0` with no guard left, nothing can raise it again.
+        // The `Scoped` keep the owner of a guard from `from_arc` or `own_with` alive themselves.
+        if data.guards.fetch_sub(1, atomic::Ordering::AcqRel) == 1 {
+            let lifted = data.lifted.load(atomic::Ordering::Acquire);
+            if lifted != 0 && data.owner.is_none() {
+                #[cfg(debug_assertions)]
+                data.poisoned.store(true, atomic::Ordering::Relaxed);
+                utils::abort_with(lifted, &data.lifts);
+            }
+            // Nothing can be lifted from a child anymore, so its parent does not have to wait
+            if let Some(parent) = &data.parent {
+                parent.release();
+            }
+        }
+    }
+}
+
+/// Configures how threads waiting for the lifted references of a [`ScopedGuard`] are woken up, e.g.
+/// in [`ScopedGuard::wait_drained`], before creating the guard. By default every guard parks them on
+/// a lock and condition variable of its own, like [`ScopedGuard::new`] does.
+///
+/// A pool of guards can share one [`Notifier`], so it is only set up once:
+///
+/// ```rust
+/// use scoped_static::{Notifier, ScopedGuardBuilder};
+///
+/// let notifier = Notifier::new();
+/// let values = [1, 2];
+/// let guards: Vec<_> = values
+///     .iter()
+///     .map(|value| unsafe { ScopedGuardBuilder::new().notifier(&notifier).build(value) })
+///     .collect();
+/// let lifted: Vec<_> = guards.iter().map(|guard| guard.lift()).collect();
+/// std::thread::spawn(move || assert_eq!(*lifted[0] + *lifted[1], 3));
+/// for guard in &guards {
+///     guard.wait_drained();
+/// }
+/// ```
+#[cfg(feature = "notify")]
+#[cfg_attr(docsrs, doc(cfg(feature = "notify")))]
+#[derive(Debug, Default)]
+pub struct ScopedGuardBuilder {
+    drained: Drained,
+}
+
+#[cfg(feature = "notify")]
+impl ScopedGuardBuilder {
+    /// Creates a new [`ScopedGuardBuilder`] with the defaults of [`ScopedGuard::new`].
+    pub fn new() -> Self {
+        ScopedGuardBuilder::default()
+    }
+
+    /// Whether waiting threads are parked and woken up when the last lifted reference is dropped.
+    /// If `false`, they wait with exponential backoff instead, like without the `notify` feature,
+    /// and dropping the last lifted reference stays a single atomic operation. Replaces a
+    /// [`Notifier`] set before.
+    pub fn notify(mut self, notify: bool) -> Self {
+        self.drained = if notify {
+            Drained::default()
+        } else {
+            Drained::off()
+        };
+        self
+    }
+
+    /// Parks waiting threads on `notifier`, which may be shared with other guards. See [`Notifier`].
+    pub fn notifier(mut self, notifier: &Notifier) -> Self {
+        self.drained = Drained::shared(notifier.clone());
+        self
+    }
+
+    /// Creates the configured [`ScopedGuard`]. See [`crate::scoped`] for a safe way to create one
+    /// with the defaults.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ScopedGuard::new`].
+    pub unsafe fn build<'a, T: 'static + ?Sized>(self, value: &'a T) -> ScopedGuard<'a, T> {
+        ScopedGuard {
+            value: NonNull::from(value),
+            data: ManuallyDrop::new(Arc::new(Shared {
+                drained: self.drained,
+                ..Shared::guarded()
+            })),
+            _scope: PhantomData,
+        }
+    }
+}
+
+/// A reference derived from a [`ScopedGuard`]. The lifetime of the underlying
+/// value has been lifted to `'static`. See [`ScopedGuard`] for more info.
+///
+/// Like `&T`, comparisons and hashing use the underlying value and not which guard it counts
+/// towards, so `Hash` agrees with `Eq` and a [`Scoped`] can be used as a map key by its value. Use
+/// [`Scoped::ptr_eq`] or [`crate::ByHandle`] to compare the handles instead.
+///
+/// Even the last [`Scoped`] cannot be turned back into a guard. The originating guard decides when
+/// the borrow ends, and a guard made from a [`Scoped`] would have no lifetime to borrow for but
+/// `'static`, which e.g. [`ScopedGuard::into_ref`] would hand out after the borrow ended. To hand
+/// off the whole scope, move the originating guard instead. Only if the value really lives for
+/// `'static` can the owner role be taken over explicitly:
+///
+/// ```rust
+/// use scoped_static::{scoped, Scoped, ScopedGuard};
+///
+/// static VALUE: f64 = 1.0;
+/// let guard = scoped!(&VALUE);
+/// let value = unsafe { Scoped::leak_static(guard.lift()) };
+/// // The new guard counts its own lifts
+/// let owner = unsafe { ScopedGuard::new(value) };
+/// assert_eq!(*owner.lift(), 1.0);
+/// ```
+pub struct Scoped<T: 'static + ?Sized> {
+    // See `ScopedGuard::value` for why this is not a reference. Kept inline rather than in `data`, so
+    // dereferencing is a single load, see the `deref` benchmark.
+    value: NonNull<T>,
+    /// Where this was created, printed if the guard is dropped while this still exists.
+    lift: LiftId,
+    data: Arc<Shared>,
+}
+
+// SAFETY: Only shared references to `T` are handed out, like `&T` does.
+unsafe impl<T: 'static + Sync + ?Sized> Send for Scoped<T> {}
+unsafe impl<T: 'static + Sync + ?Sized> Sync for Scoped<T> {}
+
+/// State shared between a [`ScopedGuard`] and the [`Scoped`] derived from it.
+#[derive(Debug, Default)]
+pub(crate) struct Shared {
+    /// The number of [`Scoped`] that exist. Each holds a strong count of the `Arc`, which aborts
+    /// before overflowing, so this cannot wrap around either.
+    lifted: AtomicUsize,
+    /// The number of clones of the [`ScopedGuard`]. `0` when the [`Scoped`] were converted from a
+    /// [`ScopedPin`], since then the [`crate::ScopedPinGuard`] is the guard.
+    guards: AtomicUsize,
+    /// The count held on a [`crate::ScopedPinGuard`] when the [`Scoped`] were converted from a
+    /// [`ScopedPin`]. It is released once the last of these [`Scoped`] is dropped.
+    pin: Option<ScopedPin<()>>,
+    /// A value stored alongside the count, which the lifted reference points into.
+    storage: Option<Storage>,
+    /// What the lifted reference points into. See [`ScopedGuard::from_arc`] and
+    /// [`ScopedGuard::own_with`].
+    owner: Option<Owner>,
+    /// The count held on the parent guard of a child guard. See [`ScopedGuard::child`].
+    parent: Option<Parent>,
+    /// Notified when `lifted` drops to zero.
+    drained: Drained,
+    /// Wakes up the tasks awaiting `lifted` to drop to zero. See [`ScopedGuard::wait_drained_async`].
+    #[cfg(feature = "tokio")]
+    drained_async: tokio::sync::Notify,
+    /// Where the [`Scoped`] that exist were created. See the `lift_backtrace` feature.
+    lifts: Lifts,
+    /// Whether the derived [`Scoped`] check `poisoned` on access. See [`ScopedGuard::new_checked`].
+    #[cfg(debug_assertions)]
+    checked: bool,
+    /// Set when the last guard is dropped while [`Scoped`] still exist.
+    #[cfg(debug_assertions)]
+    poisoned: AtomicBool,
+}
+
+impl Shared {
+    fn guarded() -> Self {
+        Shared {
+            guards: AtomicUsize::new(1),
+            ..Shared::default()
+        }
+    }
+
+    /// Whether the value is freed with the count, rather than borrowed for the lifetime of the guard.
+    fn stores_value(&self) -> bool {
+        self.storage.is_some()
+            || self.owner.is_some()
+            || self
+                .parent
+                .as_ref()
+                .is_some_and(|parent| parent.lifted.data.stores_value())
+    }
+
+    /// Blocks until `lifted` is zero or the `deadline` has passed. Returns whether it is zero.
+    #[cfg(feature = "std")]
+    fn wait_drained(&self, deadline: Option<Instant>) -> bool {
+        self.drained.wait(
+            || self.lifted.load(atomic::Ordering::Acquire) == 0,
+            deadline,
+        )
+    }
+
+    /// Awaits `lifted` to be zero.
+    #[cfg(feature = "tokio")]
+    async fn wait_drained_async(&self) {
+        loop {
+            let mut notified = core::pin::pin!(self.drained_async.notified());
+            // Registered before checking, so a drop to zero in between is not missed
+            notified.as_mut().enable();
+            if self.lifted.load(atomic::Ordering::Acquire) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Type erased storage for a value without drop glue.
+#[derive(Debug)]
+struct Storage(Box<dyn Any>);
+
+// SAFETY: `Storage` is never accessed after creation, only freed. Since the stored value has no drop
+// glue, freeing it runs no code of the stored type, which may thus happen on any thread.
+unsafe impl Send for Storage {}
+unsafe impl Sync for Storage {}
+impl UnwindSafe for Storage {}
+impl RefUnwindSafe for Storage {}
+
+/// The count a child guard holds on its parent. See [`ScopedGuard::child`].
+#[derive(Debug)]
+struct Parent {
+    lifted: ManuallyDrop<Scoped<()>>,
+    released: AtomicBool,
+}
+
+impl Parent {
+    /// Releases the count, once. Called when the last child guard is dropped with nothing lifted,
+    /// rather than only when the `Arc` is freed, which a dropped `Scoped` may still hold for a moment
+    /// after its decrement. So the parent is drained as soon as e.g. joining the child returns.
+    fn release(&self) {
+        if !self.released.swap(true, atomic::Ordering::AcqRel) {
+            // SAFETY: The flag makes this the only copy that is used or dropped
+            drop(unsafe { ptr::read(&*self.lifted) });
+        }
+    }
+}
+
+impl Drop for Parent {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Type erased owner that keeps the value of a guard from [`ScopedGuard::from_arc`] or
+/// [`ScopedGuard::own_with`] alive.
+#[derive(Debug)]
+struct Owner {
+    _owned: Box<dyn Any + Send + Sync>,
+}
+
+// `Owner` is never accessed after creation, only dropped
+impl UnwindSafe for Owner {}
+impl RefUnwindSafe for Owner {}
+
+impl<T: 'static + ?Sized> Scoped<T> {
+    /// Makes a new [`Scoped`] for a component of the underlying value. The returned [`Scoped`]
+    /// keeps the originating [`ScopedGuard`] from being dropped, just like `this` did.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::map(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let value = (1, String::from("one"));
+    /// let guard = scoped!(&value);
+    /// let lifted: Scoped<String> = Scoped::map(guard.lift(), |(_, name)| name);
+    /// assert_eq!(*lifted, "one");
+    /// ```
+    ///
+    /// This is also how a [`Scoped`] is coerced to an unsized type, e.g. a trait object:
+    ///
+    /// ```rust
+    /// use std::fmt::Display;
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let value = 1.0;
+    /// let guard = scoped!(&value);
+    /// let lifted: Scoped<dyn Display + Sync> = Scoped::map(guard.lift(), |value| value as _);
+    /// assert_eq!(lifted.to_string(), "1");
+    /// ```
+    pub fn map<U: 'static + ?Sized, F: FnOnce(&T) -> &U>(this: Self, f: F) -> Scoped<U> {
+        let value = NonNull::from(f(&*this));
+        // The count of `this` is taken over
+        let this = ManuallyDrop::new(this);
+        Scoped {
+            value,
+            lift: this.lift,
+            data: unsafe { ptr::read(&this.data) },
+        }
+    }
+
+    /// Creates a [`WeakScoped`] to the underlying value, which does not keep the originating
+    /// [`ScopedGuard`] from being dropped.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::downgrade(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let value = 1.0;
+    /// let guard = scoped!(&value);
+    /// let lifted = guard.lift();
+    /// let weak = Scoped::downgrade(&lifted);
+    /// assert_eq!(*weak.upgrade().unwrap(), 1.0);
+    /// drop(lifted);
+    /// assert!(weak.upgrade().is_none());
+    /// ```
+    pub fn downgrade(this: &Self) -> WeakScoped<T> {
+        WeakScoped {
+            value: this.value,
+            data: Arc::downgrade(&this.data),
+        }
+    }
+
+    /// Formats the underlying value, like `Scoped(value)`. The [`Debug`](fmt::Debug) of [`Scoped`]
+    /// itself only formats the address and the count.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let value = 1.0;
+    /// let guard = scoped!(&value);
+    /// let lifted = guard.lift();
+    /// assert_eq!(format!("{:?}", Scoped::debug_value(&lifted)), "Scoped(1.0)");
+    /// ```
+    ///
+    /// This is an associated function that needs to be used as `Scoped::debug_value(...)`, so that
+    /// it does not conflict with a method of the same name on the underlying value.
+    pub fn debug_value(this: &Self) -> impl fmt::Debug + '_
+    where
+        T: fmt::Debug,
+    {
+        utils::DebugValue {
+            name: "Scoped",
+            value: &**this,
+        }
+    }
+
+    /// Returns `true` if both [`Scoped`] count towards the same [`ScopedGuard`], regardless of the
+    /// underlying values. Like [`Arc::ptr_eq`].
+    ///
+    /// This is an associated function that needs to be used as `Scoped::ptr_eq(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.data, &other.data)
+    }
+
+    /// The address of the shared count, which identifies the originating guard. See
+    /// [`crate::ByHandle`].
+    pub(crate) fn guard_ptr(this: &Self) -> *const () {
+        Arc::as_ptr(&this.data).cast()
+    }
+
+    /// Returns `true` if this is the only lifted reference that the originating guard still waits
+    /// for, e.g. so a long-running task can release it early to unblock a shutdown that joins the
+    /// guard. When converted from a [`ScopedPin`], the other [`ScopedPin`] of the pinned guard count
+    /// as well.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::guard_is_sole_remaining(...)`,
+    /// so that it does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let value = 1.0;
+    /// let guard = scoped!(&value);
+    /// let lifted = guard.lift();
+    /// let cloned = lifted.clone();
+    /// assert!(!Scoped::guard_is_sole_remaining(&lifted));
+    /// drop(cloned);
+    /// assert!(Scoped::guard_is_sole_remaining(&lifted));
+    /// ```
+    pub fn guard_is_sole_remaining(this: &Self) -> bool {
+        this.data.lifted.load(atomic::Ordering::Acquire) == 1
+            && this
+                .data
+                .pin
+                .as_ref()
+                .is_none_or(|pin| ScopedPin::outstanding_count(pin) == 1)
+    }
+
+    /// Converts back into a [`ScopedPin`], the reverse of [`ScopedPin::into_arc`], e.g. to use the
+    /// cheaper pinned handle in a tight loop again. Gives `this` back if other clones of it still
+    /// exist, or if it was lifted from a [`ScopedGuard`].
+    ///
+    /// The count is only ever held in one place. While converted, the `Arc` holds the single count
+    /// on the [`crate::ScopedPinGuard`] that the [`ScopedPin`] was lifted from, and the last of its
+    /// clones hands that count back. A [`ScopedGuard`] counts in the `Arc` itself and there is no
+    /// pinned count that could take over, so lift from a [`crate::ScopedPinGuard`] instead.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::try_into_pin(...)`, so that
+    /// it does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped_pin, Scoped, ScopedPin};
+    ///
+    /// let value = 1.0;
+    /// scoped_pin!(guard, &value);
+    /// let lifted = ScopedPin::into_arc(guard.lift());
+    /// let cloned = lifted.clone();
+    /// let lifted = Scoped::try_into_pin(lifted).unwrap_err();
+    /// drop(cloned);
+    /// let lifted: ScopedPin<f64> = Scoped::try_into_pin(lifted).unwrap();
+    /// assert_eq!(guard.lifted_count(), 1);
+    /// ```
+    pub fn try_into_pin(this: Self) -> Result<ScopedPin<T>, Self>
+    where
+        T: Sized,
+    {
+        if this.data.pin.is_none() {
+            return Err(this);
+        }
+        let this = ManuallyDrop::new(this);
+        let (value, lift) = (this.value, this.lift);
+        // SAFETY: `this` is not used or dropped afterwards
+        let data = unsafe { ptr::read(&this.data) };
+        match Arc::try_unwrap(data) {
+            Ok(mut shared) => {
+                // Nothing else counts on `shared` anymore, so the count on the pinned guard moves
+                // to the returned `ScopedPin` without being released in between
+                shared.lifts.remove(lift);
+                let pin = shared.pin.take().unwrap();
+                // SAFETY: The pinned guard keeps the value alive while the count is held
+                Ok(ScopedPin::map(pin, |_| unsafe { value.as_ref() }))
+            }
+            Err(data) => Err(Scoped { value, lift, data }),
+        }
+    }
+
+    /// Dereferences the underlying value, e.g. `&f64` for a `Scoped<Box<f64>>` instead of `&**this`.
+    /// Like [`Option::as_deref`].
+    ///
+    /// This is an associated function that needs to be used as `Scoped::as_deref(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value, e.g.
+    /// [`Option::as_deref`] itself.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let value = Box::new(1.0);
+    /// let guard = scoped!(&value);
+    /// let lifted = guard.lift();
+    /// assert_eq!(*Scoped::as_deref(&lifted) + 1.0, 2.0);
+    /// ```
+    pub fn as_deref(this: &Self) -> &T::Target
+    where
+        T: Deref,
+    {
+        this
+    }
+
+    /// Gives up the count held by `this` and returns the reference with its `'static` lifetime, so
+    /// the originating [`ScopedGuard`] no longer waits for or aborts because of it. This is the
+    /// escape hatch for a value that is known to actually live for `'static`, e.g. a `static` item
+    /// or a leaked allocation, without forgetting the handle.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::leak_static(...)`, so that
+    /// it does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// static VALUE: f64 = 1.0;
+    /// let guard = scoped!(&VALUE);
+    /// let value: &'static f64 = unsafe { Scoped::leak_static(guard.lift()) };
+    /// drop(guard);
+    /// assert_eq!(*value, 1.0);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// The underlying value must be valid for `'static`, and must not be mutated or moved for as long
+    /// as the returned reference is used. Nothing stops the guard from ending the borrow afterwards,
+    /// so this is the caller's responsibility.
+    pub unsafe fn leak_static(this: Self) -> &'static T {
+        let value = this.value;
+        // Released like any other handle, so the guard can be dropped right after
+        drop(this);
+        unsafe { value.as_ref() }
+    }
+}
+
+impl<T: 'static> Scoped<[T]> {
+    /// A [`Scoped`] for the element at `index`, or `None` if it is out of bounds. It counts towards
+    /// the originating [`ScopedGuard`] like a clone of this [`Scoped`], so one guard governs the
+    /// whole slice while the elements are handed out, e.g. to different workers.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::get(...)`, so that it does
+    /// not conflict with [`slice::get`], which is still reachable as `lifted.get(...)`.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// let values = vec![1, 2, 3];
+    /// let guard = scoped!(values.as_slice());
+    /// let lifted = guard.lift();
+    /// let handles: Vec<_> = (0..lifted.len())
+    ///     .map(|index| {
+    ///         let element = Scoped::get(&lifted, index).unwrap();
+    ///         std::thread::spawn(move || *element * 2)
+    ///     })
+    ///     .collect();
+    /// let doubled: Vec<i32> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    /// assert_eq!(doubled, [2, 4, 6]);
+    /// assert!(Scoped::get(&lifted, 3).is_none());
+    /// ```
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn get(this: &Self, index: usize) -> Option<Scoped<T>> {
+        if index >= this.len() {
+            return None;
+        }
+        Some(Scoped::map(this.clone(), |slice| &slice[index]))
+    }
+}
+
+impl<T: 'static + ?Sized> Scoped<RefCell<T>> {
+    /// Immutably borrows the underlying [`RefCell`], like [`RefCell::borrow`]. Without this, a
+    /// `lifted.borrow()` resolves to [`Borrow::borrow`] whenever that trait is in scope.
+    ///
+    /// This is an associated function that needs to be used as `Scoped::borrow(...)`, so that it
+    /// does not conflict with [`Borrow::borrow`]. Since [`RefCell`] is not `Sync`, the lifted
+    /// reference stays on one thread, e.g. in a task of a `tokio::task::LocalSet`:
+    ///
+    /// ```rust
+    /// use std::cell::RefCell;
+    /// use scoped_static::{scoped, Scoped};
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let value = RefCell::new(1);
+    ///     let guard = scoped!(&value);
+    ///     let lifted = guard.lift();
+    ///     tokio::task::LocalSet::new()
+    ///         .run_until(async move {
+    ///             tokio::task::spawn_local(async move {
+    ///                 *Scoped::borrow_mut(&lifted) += 1;
+    ///                 assert_eq!(*Scoped::borrow(&lifted), 2);
+    ///             })
+    ///             .await
+    ///             .unwrap();
+    ///         })
+    ///         .await;
+    ///     assert_eq!(*value.borrow(), 2);
+    /// }
+    /// ```
+    #[track_caller]
+    pub fn borrow(this: &Self) -> Ref<'_, T> {
+        RefCell::borrow(this)
+    }
+
+    /// Mutably borrows the underlying [`RefCell`], like [`RefCell::borrow_mut`]. See
+    /// [`Scoped::borrow`].
+    #[track_caller]
+    pub fn borrow_mut(this: &Self) -> RefMut<'_, T> {
+        RefCell::borrow_mut(this)
+    }
+}
+
+impl<T: 'static + ?Sized> Deref for Scoped<T> {
+    type Target = T;
+
+    #[track_caller]
+    fn deref(&self) -> &Self::Target {
+        #[cfg(debug_assertions)]
+        assert!(
+            !(self.data.checked && self.data.poisoned.load(atomic::Ordering::Relaxed)),
+            "`Scoped` to {:p} used after its `ScopedGuard` was dropped",
+            self.value
+        );
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: 'static + ?Sized> AsRef<T> for Scoped<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: 'static + ?Sized> Borrow<T> for Scoped<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<'s, T: 'static + ?Sized> IntoIterator for &'s Scoped<T>
+where
+    &'s T: IntoIterator,
+{
+    type Item = <&'s T as IntoIterator>::Item;
+    type IntoIter = <&'s T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        <&'s T>::into_iter(self)
+    }
+}
+
+impl<T: 'static + ?Sized> Clone for Scoped<T> {
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    fn clone(&self) -> Self {
+        // Relaxed is enough, like `Arc::clone`. `self` already keeps the count above zero.
+        self.data.lifted.fetch_add(1, atomic::Ordering::Relaxed);
+        Scoped {
+            value: self.value,
+            lift: self.data.lifts.record(),
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T: 'static + ?Sized> Drop for Scoped<T> {
+    fn drop(&mut self) {
+        self.data.lifts.remove(self.lift);
+        // Release so all uses of the value through this handle happen before the guard observes the
+        // decrement.
+        if self.data.lifted.fetch_sub(1, atomic::Ordering::Release) == 1 {
+            self.data.drained.notify();
+            #[cfg(feature = "tokio")]
+            self.data.drained_async.notify_waiters();
+        }
+    }
+}
+
+impl<T: 'static> From<ScopedPin<T>> for Scoped<T> {
+    /// See [`ScopedPin::into_arc`].
+    fn from(lifted: ScopedPin<T>) -> Self {
+        let value = NonNull::from(lifted.value);
+        let pin = ScopedPin::map(lifted, |_| &());
+        // The `ScopedPin` keeps where it was lifted, since the pinned guard is the one that aborts
+        let data = Shared {
+            lifted: AtomicUsize::new(1),
+            pin: Some(pin),
+            ..Shared::default()
+        };
+        Scoped {
+            value,
+            lift: data.lifts.record(),
+            data: Arc::new(data),
+        }
+    }
+}
+
+/// Formats the address of the underlying value and the number of lifted references of its guard,
+/// without reading the value. So a handle can be logged even if something already went wrong, e.g.
+/// while unwinding from a leak with the `panic_on_leak` feature. See [`Scoped::debug_value`] to
+/// format the value.
+impl<T: 'static + ?Sized> fmt::Debug for Scoped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The count is owned by the `Arc`, so it is valid as long as this handle is
+        f.debug_struct("Scoped")
+            .field("ptr", &self.value.as_ptr())
+            .field("lifted_count", &self.data.lifted.load(atomic::Ordering::Relaxed))
+            .finish()
+    }
+}
+
+impl<T: 'static + fmt::Display + ?Sized> fmt::Display for Scoped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+impl<T: 'static + ?Sized> fmt::Pointer for Scoped<T> {
+    /// Formats the address of the underlying value, like `&T` does. Not the address of the count.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.value, f)
+    }
+}
+
+impl<T: 'static + PartialEq + ?Sized> PartialEq for Scoped<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: 'static + PartialEq + ?Sized> PartialEq<T> for Scoped<T> {
+    fn eq(&self, other: &T) -> bool {
+        **self == *other
+    }
+}
+
+impl<T: 'static + PartialEq + ?Sized> PartialEq<&T> for Scoped<T> {
+    fn eq(&self, other: &&T) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: 'static + Eq + ?Sized> Eq for Scoped<T> {}
+
+impl<T: 'static + PartialOrd + ?Sized> PartialOrd for Scoped<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: 'static + PartialOrd + ?Sized> PartialOrd<T> for Scoped<T> {
+    fn partial_cmp(&self, other: &T) -> Option<Ordering> {
+        (**self).partial_cmp(other)
+    }
+}
+
+impl<T: 'static + PartialOrd + ?Sized> PartialOrd<&T> for Scoped<T> {
+    fn partial_cmp(&self, other: &&T) -> Option<Ordering> {
+        (**self).partial_cmp(*other)
+    }
+}
+
+impl<T: 'static + Ord + ?Sized> Ord for Scoped<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: 'static + Hash + ?Sized> Hash for Scoped<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+/// Serializes the underlying value, like `&T` does.
+///
+/// There is intentionally no `Deserialize`, since a deserialized value would have no
+/// [`ScopedGuard`] to borrow from. Deserialize into `T` and lift a reference to it instead.
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<T: 'static + serde::Serialize + ?Sized> serde::Serialize for Scoped<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// A weak version of [`Scoped`], created with [`Scoped::downgrade`]. It does not count towards
+/// the lifted references of the originating [`ScopedGuard`], so it never keeps the guard from
+/// being dropped. It has to be upgraded to access the underlying value.
+pub struct WeakScoped<T: 'static + ?Sized> {
+    value: NonNull<T>,
+    data: Weak<Shared>,
+}
+
+// SAFETY: Only shared references to `T` are handed out after upgrading, like `&T` does.
+unsafe impl<T: 'static + Sync + ?Sized> Send for WeakScoped<T> {}
+unsafe impl<T: 'static + Sync + ?Sized> Sync for WeakScoped<T> {}
+
+impl<T: 'static + ?Sized> WeakScoped<T> {
+    /// Attempts to upgrade to a [`Scoped`], which again keeps the originating [`ScopedGuard`] from
+    /// being dropped. Returns `None` once no [`Scoped`] derived from the guard remain, which is
+    /// always the case once the guard has been dropped.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn upgrade(&self) -> Option<Scoped<T>> {
+        let data = self.data.upgrade()?;
+        // Only raised while another `Scoped` exists, so a guard that observed `0` is never
+        // invalidated. Acquire like `Weak::upgrade`.
+        data.lifted
+            .fetch_update(atomic::Ordering::Acquire, atomic::Ordering::Relaxed, |count| {
+                (count != 0).then_some(count + 1)
+            })
+            .ok()?;
+        Some(Scoped {
+            value: self.value,
+            lift: data.lifts.record(),
+            data,
+        })
+    }
+}
+
+impl<T: 'static + ?Sized> Clone for WeakScoped<T> {
+    fn clone(&self) -> Self {
+        WeakScoped {
+            value: self.value,
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T: 'static + ?Sized> fmt::Debug for WeakScoped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(WeakScoped)")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    struct NonCopy(f32);
+
+    impl NonCopy {
+        pub fn new() -> Self {
+            NonCopy(1.0)
+        }
+        pub fn access_value(&self) {
+            assert_eq!(self.0, 1.0, "If these values are not equal it signals UB");
+        }
+    }
+
+    #[cfg(test)]
+    mod normal_tests {
+        use super::super::{Scoped, ScopedGuard};
+        use crate::{ScopedPin, ScopedPinGuard};
+        use super::NonCopy;
+        use std::cell::RefCell;
+        use std::collections::HashSet;
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        #[test]
+        fn dangling() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            let result = std::panic::catch_unwind(|| {
+                std::mem::drop(guard);
+            });
+            assert!(
+                result.is_err(),
+                "expected panic when dropping ScopeGuard with an alive Scoped"
+            );
+        }
+
+        #[test]
+        fn valid() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
+
+        #[cfg(feature = "lift_backtrace")]
+        #[test]
+        fn outstanding_backtraces() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            assert!(guard.outstanding_backtraces().is_empty());
+            let lifted1 = guard.lift();
+            let lifted2 = guard.lift();
+            let released = guard.lift();
+            std::mem::drop(released);
+            assert_eq!(guard.outstanding_backtraces().len(), 2);
+            std::mem::drop(lifted1);
+            assert_eq!(guard.outstanding_backtraces().len(), 1);
+            std::mem::drop(lifted2);
+            assert!(guard.outstanding_backtraces().is_empty());
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn lifted_count() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            assert_eq!(guard.lifted_count(), 0);
+            let lifted1 = guard.lift();
+            let lifted2 = guard.lift();
+            let lifted3 = guard.lift();
+            assert_eq!(guard.lifted_count(), 3);
+            std::mem::drop(lifted1);
+            assert_eq!(guard.lifted_count(), 2);
+            std::mem::drop(lifted2);
+            std::mem::drop(lifted3);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn clone_guard() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard1 = unsafe { ScopedGuard::new(ref_value) };
+            let guard2 = unsafe { guard1.clone_guard() };
+            let lifted = guard1.lift();
+            assert_eq!(guard2.lifted_count(), 1);
+            // Another clone still guards the value
+            std::mem::drop(guard1);
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert_eq!(guard2.lifted_count(), 0);
+            std::mem::drop(guard2);
+        }
+
+        #[test]
+        fn clone_guard_dangling() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard1 = unsafe { ScopedGuard::new(ref_value) };
+            let guard2 = unsafe { guard1.clone_guard() };
+            let lifted = guard2.lift();
+            std::mem::drop(guard2);
+            let result = std::panic::catch_unwind(|| {
+                std::mem::drop(guard1);
+            });
+            assert!(
+                result.is_err(),
+                "expected panic when dropping the last ScopeGuard clone with an alive Scoped"
+            );
+            std::mem::drop(lifted);
+        }
+
+        #[test]
+        fn reuse() {
+            let rounds = if cfg!(miri) { 100 } else { 100_000 };
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            for _ in 0..rounds {
+                let lifted = guard.lift();
+                let cloned = lifted.clone();
+                cloned.access_value();
+                std::mem::drop((lifted, cloned));
+                assert_eq!(guard.lifted_count(), 0);
+            }
+            for _ in 0..10 {
+                let lifted = guard.lift();
+                std::thread::spawn(move || lifted.access_value())
+                    .join()
+                    .unwrap();
+                assert_eq!(guard.lifted_count(), 0);
+            }
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn replace() {
+            let first = Box::new(NonCopy::new());
+            let second = Box::new(NonCopy(2.0));
+            let mut guard = unsafe { ScopedGuard::new(&first) };
+            let lifted = guard.lift();
+            let weak = Scoped::downgrade(&lifted);
+            let rejected = guard.replace(&second).err().unwrap();
+            assert!(std::ptr::eq(rejected, &second));
+            assert!(std::ptr::eq(&*guard, &first));
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert!(guard.replace(&second).is_ok());
+            assert!(std::ptr::eq(&*guard, &second));
+            let lifted = guard.lift();
+            assert_eq!(lifted.0, 2.0);
+            // A weak reference to the first value stays valid, since it is borrowed for as long
+            weak.upgrade().unwrap().access_value();
+            // Clones of the guard keep their value
+            let cloned = unsafe { guard.clone_guard() };
+            std::mem::drop(lifted);
+            assert!(guard.replace(&first).is_ok());
+            assert!(std::ptr::eq(&*cloned, &second));
+            std::mem::drop((guard, cloned));
+        }
+
+        #[test]
+        fn checked() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new_checked(ref_value) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        #[cfg(debug_assertions)]
+        fn checked_dangling() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new_checked(ref_value) };
+            let lifted = guard.lift();
+            let result = std::panic::catch_unwind(|| {
+                std::mem::drop(guard);
+            });
+            assert!(result.is_err(), "expected the leak handler to panic");
+            // Recovered from the drop, so `lifted` outlives the guard. The value is still alive here,
+            // but in general it may not be.
+            let result = std::panic::catch_unwind(|| {
+                lifted.access_value();
+            });
+            let message = result.unwrap_err();
+            let message = message.downcast_ref::<String>().unwrap();
+            assert!(
+                message.contains("used after its `ScopedGuard` was dropped"),
+                "unexpected panic message: {message}"
+            );
+            std::mem::drop(lifted);
+        }
+
+        #[test]
+        fn try_lift() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted1 = guard.try_lift(2).unwrap();
+            let lifted2 = guard.try_lift(2).unwrap();
+            assert!(guard.try_lift(2).is_none());
+            std::mem::drop(lifted1);
+            let lifted3 = guard.try_lift(2).unwrap();
+            std::mem::drop(lifted2);
+            std::mem::drop(lifted3);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn try_lift_concurrent() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let barrier = std::sync::Barrier::new(8);
+            let lifted: Vec<_> = std::thread::scope(|s| {
+                let handles: Vec<_> = (0..8)
+                    .map(|_| {
+                        s.spawn(|| {
+                            barrier.wait();
+                            guard.try_lift(2)
+                        })
+                    })
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            });
+            assert_eq!(lifted.iter().flatten().count(), 2);
+            assert_eq!(guard.lifted_count(), 2);
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn lifted_count_independent_of_arc() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            // Weak references and other internal shares of the allocation are not lifts
+            let weak = Scoped::downgrade(&lifted);
+            let data = std::sync::Arc::clone(&guard.data);
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(lifted);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+            std::mem::drop(data);
+            assert!(weak.upgrade().is_none());
+        }
+
+        #[test]
+        fn try_unwrap() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            let guard = match guard.try_unwrap() {
+                Ok(()) => panic!("expected the guard back while a Scoped is alive"),
+                Err(guard) => guard,
+            };
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert!(guard.try_unwrap().is_ok());
+        }
+
+        #[test]
+        fn as_ref_a() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            let borrowed = guard.as_ref_a().unwrap();
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+            borrowed.access_value();
+
+            let (first, second) = (Box::new(NonCopy::new()), Box::new(NonCopy::new()));
+            let mut guard = unsafe { ScopedGuard::new(&first) };
+            assert!(guard.replace(&second).is_ok());
+            let borrowed = guard.as_ref_a().unwrap();
+            std::mem::drop(guard);
+            assert!(std::ptr::eq(borrowed, &second));
+
+            let name = String::from("one");
+            let guard = unsafe { ScopedGuard::new_tuple((&first, name.as_str())) };
+            assert!(guard.as_ref_a().is_none(), "the tuple is freed with the guard");
+        }
+
+        #[test]
+        fn try_into_pin() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = ScopedPin::into_arc(guard.lift());
+            let cloned = lifted.clone();
+            let Err(lifted) = Scoped::try_into_pin(lifted) else {
+                panic!("expected the Scoped back while a clone exists");
+            };
+            std::mem::drop(cloned);
+            let Ok(pinned) = Scoped::try_into_pin(lifted) else {
+                panic!("expected the last clone to convert");
+            };
+            assert_eq!(guard.lifted_count(), 1);
+            pinned.access_value();
+            // Round trips keep the single count
+            let Ok(pinned) = Scoped::try_into_pin(ScopedPin::into_arc(pinned)) else {
+                panic!("expected the last clone to convert");
+            };
+            assert_eq!(guard.lifted_count(), 1);
+
+            // The pinned guard still aborts while the converted handle exists
+            std::mem::forget(pinned);
+            let result = std::panic::catch_unwind(|| std::mem::drop(guard_unpinned));
+            assert!(result.is_err());
+
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let Err(lifted) = Scoped::try_into_pin(guard.lift()) else {
+                panic!("expected the Scoped back without a pinned count to take over");
+            };
+            std::mem::drop(lifted);
+        }
+
+        #[test]
+        fn from_arc() {
+            let arc = Arc::new(NonCopy::new());
+            let guard = ScopedGuard::from_arc(Arc::clone(&arc));
+            let lifted = guard.lift();
+            let cloned = lifted.clone();
+            assert_eq!(guard.lifted_count(), 2);
+            assert!(guard.as_ref_a().is_none());
+            // Dropping the guard while lifted references exist is fine in this mode
+            std::mem::drop(guard);
+            let handle = std::thread::spawn(move || cloned.access_value());
+            lifted.access_value();
+            handle.join().unwrap();
+            assert_eq!(Arc::strong_count(&arc), 2);
+            std::mem::drop(lifted);
+            assert_eq!(Arc::strong_count(&arc), 1, "the last Scoped releases the Arc");
+
+            let guard = ScopedGuard::from_arc(Arc::clone(&arc));
+            let lifted = guard.lift();
+            std::mem::forget(guard);
+            lifted.access_value();
+        }
+
+        #[test]
+        fn own_with() {
+            let values = vec![NonCopy::new(), NonCopy::new()];
+            let guard = ScopedGuard::own_with(values, |values| &values[1]);
+            let lifted = guard.lift();
+            let cloned = lifted.clone();
+            let handle = std::thread::spawn(move || cloned.access_value());
+            lifted.access_value();
+            handle.join().unwrap();
+            assert!(guard.as_ref_a().is_none());
+            // The lifted reference keeps the `Vec` alive past the guard
+            std::mem::drop(guard);
+            lifted.access_value();
+
+            let guard = ScopedGuard::own_with(String::from("one"), |name| name.as_str());
+            let lifted = guard.lift();
+            std::mem::drop(lifted);
+            assert!(guard.is_drained());
+            assert_eq!(&*guard, "one");
+        }
+
+        #[test]
+        fn child() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let parent = unsafe { ScopedGuard::new(ref_value) };
+            let first = parent.child();
+            let second = parent.child();
+            let first_lifted: Vec<_> = (0..2).map(|_| first.lift()).collect();
+            let second_lifted = second.lift();
+            assert_eq!(parent.lifted_count(), 2, "one per child");
+            assert_eq!(first.lifted_count(), 2);
+            assert_eq!(second.lifted_count(), 1);
+
+            let handle = std::thread::spawn(move || {
+                for lifted in first_lifted {
+                    std::thread::sleep(Duration::from_millis(10));
+                    lifted.access_value();
+                }
+            });
+            first.join();
+            handle.join().unwrap();
+            assert_eq!(parent.lifted_count(), 1, "the second child still holds its count");
+            second_lifted.access_value();
+            std::mem::drop(second_lifted);
+            second.join();
+            assert!(parent.is_drained());
+
+            let child = parent.child();
+            let lifted = child.lift();
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                std::mem::drop(parent);
+            }));
+            assert!(result.is_err(), "the parent outlives its children");
+            // The value is still borrowed here, unlike after a real abort
+            std::mem::drop(lifted);
+            std::mem::drop(child);
+        }
+
+        #[test]
+        fn try_from_ptr() {
+            use crate::NewError;
+
+            let values = [1u32, 2];
+            let guard = unsafe { ScopedGuard::try_from_ptr(&values[1] as *const u32) }.unwrap();
+            assert_eq!(*guard.lift(), 2);
+
+            let null = unsafe { ScopedGuard::<u32>::try_from_ptr(std::ptr::null()) };
+            assert_eq!(null.err(), Some(NewError::Null));
+
+            // Never dereferenced, only checked
+            let misaligned = values.as_ptr().cast::<u8>().wrapping_add(1).cast::<u32>();
+            let error = unsafe { ScopedGuard::try_from_ptr(misaligned) }.err().unwrap();
+            assert_eq!(
+                error,
+                NewError::Misaligned {
+                    addr: misaligned.addr(),
+                    align: 4
+                }
+            );
+            assert!(error.to_string().contains("aligned to 4"), "{error}");
+        }
+
+        #[test]
+        fn lock_guards() {
+            let values = std::sync::Mutex::new(vec![1, 2, 3]);
+            let locked = values.lock().unwrap();
+            let guard = unsafe { ScopedGuard::new(&*locked) };
+            let lifted = guard.lift();
+            let sum = std::thread::spawn(move || lifted.iter().sum::<i32>());
+            assert_eq!(sum.join().unwrap(), 6);
+            std::mem::drop(guard);
+            std::mem::drop(locked);
+            assert!(values.try_lock().is_ok());
+
+            let value = std::sync::RwLock::new(NonCopy::new());
+            let read = value.read().unwrap();
+            let guard = unsafe { ScopedGuard::new(&*read) };
+            let lifted = guard.lift();
+            // Other readers can still lock it
+            assert!(value.try_read().is_ok());
+            std::thread::spawn(move || lifted.access_value())
+                .join()
+                .unwrap();
+            std::mem::drop(guard);
+            std::mem::drop(read);
+        }
+
+        #[test]
+        fn lock_guard_escape() {
+            let value = std::sync::Mutex::new(NonCopy::new());
+            let result = std::panic::catch_unwind(|| {
+                let locked = value.lock().unwrap();
+                let guard = unsafe { ScopedGuard::new(&*locked) };
+                // Escapes past both guards
+                std::mem::forget(guard.lift());
+                std::mem::drop(guard);
+                unreachable!("the lock must not be released with a lifted reference alive");
+            });
+            assert!(result.is_err());
+            // The lock was still held while unwinding from the leak
+            assert!(value.is_poisoned());
+        }
+
+        #[test]
+        fn lift_on_demand() {
+            let value = NonCopy::new();
+            let guard = unsafe { ScopedGuard::new(&value) };
+            {
+                let _lift = guard.lift_on_demand();
+                assert_eq!(guard.lifted_count(), 0);
+            }
+            let lift = guard.lift_on_demand();
+            let handles = [lift(), lift()];
+            assert_eq!(guard.lifted_count(), 2);
+            handles.iter().for_each(|handle| handle.access_value());
+            drop(handles);
+            assert_eq!(guard.lifted_count(), 0);
+        }
+
+        #[test]
+        fn join() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted.access_value();
+            });
+            guard.join();
+        }
+
+        #[test]
+        fn block_until() {
+            use std::collections::VecDeque;
+            use std::future::Future;
+            use std::pin::Pin;
+            use std::task::{Context, Poll, Waker};
+
+            /// Holds its `Scoped` until it was polled `polls` times.
+            struct Task {
+                lifted: Option<Scoped<Box<NonCopy>>>,
+                polls: usize,
+            }
+
+            impl Future for Task {
+                type Output = ();
+
+                fn poll(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<()> {
+                    self.polls -= 1;
+                    if self.polls > 0 {
+                        return Poll::Pending;
+                    }
+                    self.lifted.take().unwrap().access_value();
+                    Poll::Ready(())
+                }
+            }
+
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let mut tasks: VecDeque<_> = (1..=3)
+                .map(|polls| Task {
+                    lifted: Some(guard.lift()),
+                    polls,
+                })
+                .collect();
+            let mut polled = 0;
+            // Polls one task at a time on this thread, like a minimal executor
+            guard.block_until(|| {
+                polled += 1;
+                let mut task = tasks.pop_front().unwrap();
+                if Pin::new(&mut task)
+                    .poll(&mut Context::from_waker(Waker::noop()))
+                    .is_pending()
+                {
+                    tasks.push_back(task);
+                }
+            });
+            assert!(tasks.is_empty());
+            assert_eq!(polled, 6);
+
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            guard.block_until(|| unreachable!("already drained"));
+        }
+
+        #[test]
+        fn join_timeout() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            let guard = match guard.join_timeout(Duration::from_millis(10)) {
+                Ok(()) => panic!("expected the guard back while a Scoped is alive"),
+                Err(guard) => guard,
+            };
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted.access_value();
+            });
+            assert!(guard.join_timeout(Duration::from_secs(10)).is_ok());
+            handle.join().unwrap();
+        }
+
+        #[test]
+        fn wait_drained() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            assert!(guard.is_drained());
+            let lifted1 = guard.lift();
+            let lifted2 = lifted1.clone();
+            assert!(!guard.is_drained());
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted1.access_value();
+                std::mem::drop(lifted1);
+                std::thread::sleep(Duration::from_millis(50));
+                lifted2.access_value();
+            });
+            guard.wait_drained();
+            assert!(guard.is_drained());
+            // Returns right away once drained
+            guard.wait_drained();
+            handle.join().unwrap();
+            std::mem::drop(guard);
+        }
+
+        #[cfg(feature = "notify")]
+        #[test]
+        fn builder_shared_notifier() {
+            use crate::{Notifier, ScopedGuardBuilder};
+
+            let first = Box::new(NonCopy::new());
+            let second = Box::new(NonCopy::new());
+            let notifier = Notifier::new();
+            let first_guard = unsafe { ScopedGuardBuilder::new().notifier(&notifier).build(&first) };
+            let second_guard =
+                unsafe { ScopedGuardBuilder::new().notifier(&notifier).build(&second) };
+            let lifted_first = first_guard.lift();
+            let lifted_second = second_guard.lift();
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted_first.access_value();
+                std::mem::drop(lifted_first);
+                // Waking up the threads waiting on the first guard must not drain the second one
+                std::thread::sleep(Duration::from_millis(50));
+                lifted_second.access_value();
+            });
+            let waiter = std::thread::scope(|scope| {
+                let waiter = scope.spawn(|| second_guard.wait_drained());
+                first_guard.wait_drained();
+                assert!(first_guard.is_drained());
+                waiter.join()
+            });
+            waiter.unwrap();
+            assert!(second_guard.is_drained());
+            handle.join().unwrap();
+            std::mem::drop((first_guard, second_guard));
+        }
+
+        #[cfg(feature = "parking_lot")]
+        #[test]
+        fn parking_lot_wait_drained() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted.access_value();
+            });
+            let Err(guard) = guard.join_timeout(Duration::from_millis(1)) else {
+                panic!("expected the guard back while a Scoped is alive");
+            };
+            let waiters = std::thread::scope(|scope| {
+                let waiter = scope.spawn(|| guard.wait_drained());
+                guard.wait_drained();
+                assert!(guard.is_drained());
+                waiter.join()
+            });
+            waiters.unwrap();
+            handle.join().unwrap();
+            assert!(guard.join_timeout(Duration::ZERO).is_ok());
+        }
+
+        #[cfg(feature = "notify")]
+        #[test]
+        fn builder_notify_off() {
+            use crate::ScopedGuardBuilder;
+
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuardBuilder::new().notify(false).build(&concrete_value) };
+            let lifted = guard.lift();
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted.access_value();
+            });
+            let guard = guard.join_timeout(Duration::from_millis(1)).unwrap_err();
+            guard.wait_drained();
+            assert!(guard.is_drained());
+            handle.join().unwrap();
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn into_ref() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            let guard = match guard.into_ref() {
+                Ok(_) => panic!("expected the guard back while a Scoped is alive"),
+                Err(guard) => guard,
+            };
+            std::mem::drop(lifted);
+            let Ok(value) = guard.into_ref() else {
+                panic!("expected the reference once no Scoped remain");
+            };
+            value.access_value();
+            assert!(std::ptr::eq(value, &*concrete_value));
+        }
+
+        #[test]
+        fn into_ref_stored() {
+            let number = Box::new(1.0);
+            let guard = unsafe { ScopedGuard::new_tuple((&*number,)) };
+            let guard = match guard.into_ref() {
+                Ok(_) => panic!("expected the guard back, since the tuple is stored by the guard"),
+                Err(guard) => guard,
+            };
+            assert_eq!(*guard.get().0, 1.0);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn spawn() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let handle = guard.spawn(|lifted| {
+                lifted.access_value();
+                lifted.0
+            });
+            assert_eq!(handle.join().unwrap(), 1.0);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn with_lifted() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let result = guard.with_lifted(|lifted| {
+                std::thread::spawn(move || {
+                    lifted.access_value();
+                    lifted.0
+                })
+            });
+            assert_eq!(result, 1.0);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn with_lifted_panic() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                guard.with_lifted(|lifted| {
+                    std::thread::spawn(move || {
+                        lifted.access_value();
+                        panic!("thread panicked");
+                    })
+                })
+            }));
+            let payload = result.expect_err("expected the panic of the thread to be resumed");
+            assert_eq!(payload.downcast_ref::<&str>(), Some(&"thread panicked"));
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[cfg(feature = "tokio")]
+        #[tokio::test]
+        async fn with_lifted_task() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let result = guard
+                .with_lifted_task(|lifted| {
+                    tokio::spawn(async move {
+                        tokio::task::yield_now().await;
+                        lifted.access_value();
+                        lifted.0
+                    })
+                })
+                .await;
+            assert_eq!(result, 1.0);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[cfg(feature = "tokio")]
+        #[tokio::test]
+        async fn join_async() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            let released = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+            let task_released = released.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                lifted.access_value();
+                task_released.store(true, std::sync::atomic::Ordering::Relaxed);
+            });
+            guard.wait_drained_async().await;
+            assert!(released.load(std::sync::atomic::Ordering::Relaxed));
+            assert!(guard.join_async(None).await);
+            std::mem::drop(guard);
+        }
+
+        #[cfg(feature = "tokio")]
+        #[tokio::test]
+        async fn join_async_timeout() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            let (release, released) = tokio::sync::oneshot::channel::<()>();
+            let task = tokio::spawn(async move {
+                released.await.unwrap();
+                lifted.access_value();
+            });
+            assert!(
+                !guard.join_async(Some(Duration::from_millis(10))).await,
+                "the task still holds its Scoped"
+            );
+            assert_eq!(guard.lifted_count(), 1);
+            release.send(()).unwrap();
+            task.await.unwrap();
+            assert!(guard.join_async(Some(Duration::ZERO)).await);
+            std::mem::drop(guard);
+        }
+
+        #[cfg(feature = "tokio")]
+        #[tokio::test]
+        async fn join_async_cancelled() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            // Drops the pending future, like a lost `tokio::select!` branch
+            let result =
+                tokio::time::timeout(Duration::from_millis(10), guard.join_async(None)).await;
+            assert!(result.is_err());
+            assert_eq!(guard.lifted_count(), 1);
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert!(guard.join_async(None).await);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn spawn_dangling() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let (sender, receiver) = std::sync::mpsc::channel::<()>();
+            // Detached, so only the count keeps the guard from being dropped
+            let handle = guard.spawn(move |lifted| {
+                receiver.recv().unwrap();
+                lifted.access_value();
+            });
+            let result = std::panic::catch_unwind(|| {
+                std::mem::drop(guard);
+            });
+            assert!(
+                result.is_err(),
+                "expected panic when dropping ScopeGuard with a Scoped alive on a detached thread"
+            );
+            sender.send(()).unwrap();
+            handle.join().unwrap();
+        }
+
+        #[test]
+        fn spawn_in_thread_scope() {
+            let concrete_value = Box::new(NonCopy::new());
+            std::thread::scope(|s| {
+                s.spawn(|| {
+                    let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+                    // `std::thread::spawn` requires `'static`, unlike the enclosing scoped thread
+                    let handle = guard.spawn(|lifted| lifted.access_value());
+                    handle.join().unwrap();
+                    std::mem::drop(guard);
+                });
+            });
+        }
 
-impl<'a, T: 'static> ScopedGuard<'a, T> {
-    /// Creates a new [`ScopedGuard`]. See [`scoped`] for a safe way to create.
-    pub unsafe fn new(value: &'a T) -> Self {
-        let value = unsafe { mem::transmute::<&'a T, &'static T>(value) };
-        let value = Arc::new(value);
-        ScopedGuard {
-            data: value,
-            _scope: std::marker::PhantomData,
+        #[test]
+        fn as_ref_and_borrow() {
+            fn len(value: impl AsRef<String>) -> usize {
+                value.as_ref().len()
+            }
+
+            let keys = Box::new(["one", "two"]);
+            let name = Box::new(String::from("three"));
+            let guard1 = unsafe { ScopedGuard::new(&keys[0]) };
+            let guard2 = unsafe { ScopedGuard::new(&keys[1]) };
+            let guard3 = unsafe { ScopedGuard::new(&*name) };
+            // The shared count has interior mutability, but only the value is hashed
+            #[allow(clippy::mutable_key_type)]
+            let mut set = HashSet::new();
+            set.insert(guard1.lift());
+            set.insert(guard2.lift());
+            assert!(set.contains(&"one"));
+            assert!(set.contains(&"two"));
+            assert!(!set.contains(&"three"));
+            assert_eq!(len(guard3.lift()), 5);
+            std::mem::drop(set);
+            std::mem::drop(guard1);
+            std::mem::drop(guard2);
+            std::mem::drop(guard3);
         }
-    }
 
-    /// Lifts this reference with lifetime `'a` into `'static` and relies on runtime
-    /// checks to ensure safety.
-    pub fn lift(&self) -> Scoped<T> {
-        return Scoped(self.data.clone());
-    }
-}
+        #[test]
+        fn debug() {
+            /// Fails the test if its `Debug` is ever called.
+            struct Unreadable;
 
-impl<'a, T> Deref for ScopedGuard<'a, T> {
-    type Target = T;
+            impl std::fmt::Debug for Unreadable {
+                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    panic!("the value must not be read")
+                }
+            }
 
-    fn deref(&self) -> &Self::Target {
-        self.data.as_ref()
-    }
-}
+            let value = Box::new(Unreadable);
+            let guard = unsafe { ScopedGuard::new(&*value) };
+            let lifted = guard.lift();
+            let _cloned = lifted.clone();
+            let debug = format!("{lifted:?}");
+            assert_eq!(
+                debug,
+                format!("Scoped {{ ptr: {lifted:p}, lifted_count: 2 }}")
+            );
 
-impl<'a, T: 'static> Drop for ScopedGuard<'a, T> {
-    fn drop(&mut self) {
-        if std::sync::Arc::strong_count(&self.data) != 1 {
-            utils::abort();
+            let value = Box::new(String::from("one"));
+            let guard = unsafe { ScopedGuard::new(&*value) };
+            let lifted = guard.lift();
+            assert_eq!(
+                format!("{:?}", Scoped::debug_value(&lifted)),
+                "Scoped(\"one\")"
+            );
+            assert_eq!(
+                format!("{guard:?}"),
+                "ScopedGuard { value: \"one\", lifted_count: 1, .. }"
+            );
         }
-    }
-}
 
-/// A reference derived from a [`ScopedGuard`]. The lifetime of the underlying
-/// value has been lifted to `'static`. See [`ScopedGuard`] for more info.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct Scoped<T: 'static>(Arc<&'static T>);
+        #[test]
+        fn hash_matches_eq() {
+            use std::hash::BuildHasher;
 
-impl<T: 'static> Deref for Scoped<T> {
-    type Target = T;
+            let one = Box::new(String::from("one"));
+            let other_one = Box::new(String::from("one"));
+            let guard1 = unsafe { ScopedGuard::new(&*one) };
+            let guard2 = unsafe { ScopedGuard::new(&*other_one) };
+            let (lifted1, lifted2) = (guard1.lift(), guard2.lift());
+            assert!(!Scoped::ptr_eq(&lifted1, &lifted2));
+            assert_eq!(lifted1, lifted2);
+            let hasher = std::hash::RandomState::new();
+            assert_eq!(hasher.hash_one(&lifted1), hasher.hash_one(&lifted2));
+            assert_eq!(hasher.hash_one(&lifted1), hasher.hash_one(&*one));
 
-    fn deref(&self) -> &Self::Target {
-        self.0.as_ref()
-    }
-}
+            #[allow(clippy::mutable_key_type)]
+            let mut set = HashSet::new();
+            assert!(set.insert(lifted1));
+            assert!(!set.insert(lifted2));
+            assert_eq!(set.len(), 1);
+            std::mem::drop(set);
+            std::mem::drop(guard1);
+            std::mem::drop(guard2);
+        }
 
-#[cfg(test)]
-mod tests {
-    struct NonCopy(f32);
+        #[test]
+        fn into_iter() {
+            let concrete_value = Box::new(vec![1, 2, 3]);
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            let mut sum = 0;
+            for value in &lifted {
+                sum += value;
+            }
+            assert_eq!(sum, 6);
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
 
-    impl NonCopy {
-        pub fn new() -> Self {
-            NonCopy(1.0)
+        #[test]
+        fn display() {
+            let name = Box::new(String::from("one"));
+            let number = Box::new(1.5f64);
+            let name_guard = unsafe { ScopedGuard::new(&*name) };
+            let number_guard = unsafe { ScopedGuard::new(&*number) };
+            let lifted_name = name_guard.lift();
+            let lifted_number = number_guard.lift();
+            assert_eq!(lifted_name.to_string(), *name);
+            assert_eq!(format!("{lifted_name:>6}"), format!("{:>6}", *name));
+            assert_eq!(format!("{lifted_number:.3}"), format!("{:.3}", *number));
+            std::mem::drop(lifted_name);
+            std::mem::drop(lifted_number);
+            std::mem::drop(name_guard);
+            std::mem::drop(number_guard);
         }
-        pub fn access_value(&self) {
-            assert_eq!(self.0, 1.0, "If these values are not equal it signals UB");
+
+        #[test]
+        fn pointer() {
+            let value = (1, String::from("one"));
+            let guard = unsafe { ScopedGuard::new(&value) };
+            let lifted = guard.lift();
+            let cloned = lifted.clone();
+            assert_eq!(format!("{lifted:p}"), format!("{cloned:p}"));
+            assert_eq!(format!("{lifted:p}"), format!("{:p}", &value));
+            let name = Scoped::map(cloned, |(_, name)| name);
+            assert_eq!(format!("{name:p}"), format!("{:p}", &value.1));
+            std::mem::drop((lifted, name));
+            std::mem::drop(guard);
         }
-    }
 
-    #[cfg(test)]
-    mod normal_tests {
-        use super::super::ScopedGuard;
-        use super::NonCopy;
+        #[test]
+        fn map() {
+            let concrete_value = Box::new((1u32, String::from("one")));
+            let ref_value = &*concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted: Scoped<String> = Scoped::map(guard.lift(), |(_, name)| name);
+            assert_eq!(*lifted, "one");
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
 
         #[test]
-        fn dangling() {
+        fn map_dangling() {
+            let concrete_value = Box::new((1u32, String::from("one")));
+            let ref_value = &*concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted: Scoped<String> = Scoped::map(guard.lift(), |(_, name)| name);
+            assert_eq!(*lifted, "one");
+            let result = std::panic::catch_unwind(|| {
+                std::mem::drop(guard);
+            });
+            assert!(
+                result.is_err(),
+                "expected panic when dropping ScopeGuard with an alive mapped Scoped"
+            );
+        }
+
+        #[test]
+        fn weak_upgrade() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
             let guard = unsafe { ScopedGuard::new(ref_value) };
             let lifted = guard.lift();
-            lifted.access_value();
+            let weak = Scoped::downgrade(&lifted);
+            assert_eq!(guard.lifted_count(), 1);
+            let upgraded = weak.upgrade().unwrap();
+            upgraded.access_value();
+            assert_eq!(guard.lifted_count(), 2);
+            std::mem::drop(lifted);
+            std::mem::drop(upgraded);
+            assert!(
+                weak.upgrade().is_none(),
+                "upgrading should fail once no Scoped remain"
+            );
+            std::mem::drop(guard);
+            assert!(weak.upgrade().is_none());
+        }
+
+        #[test]
+        fn weak_upgrade_after_guard_drop() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let weak = Scoped::downgrade(&guard.lift());
+            std::mem::drop(guard);
+            assert!(weak.upgrade().is_none());
+        }
+
+        #[test]
+        fn weak_upgrade_blocks_guard() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            let weak = Scoped::downgrade(&lifted);
+            let upgraded = weak.upgrade().unwrap();
+            std::mem::drop(lifted);
             let result = std::panic::catch_unwind(|| {
                 std::mem::drop(guard);
             });
             assert!(
                 result.is_err(),
-                "expected panic when dropping ScopeGuard with an alive Scoped"
+                "expected panic when dropping ScopeGuard with an alive upgraded Scoped"
             );
+            std::mem::drop(upgraded);
         }
 
         #[test]
-        fn valid() {
+        fn unsize() {
+            trait Access {
+                fn access(&self) -> f32;
+            }
+
+            impl Access for NonCopy {
+                fn access(&self) -> f32 {
+                    self.access_value();
+                    self.0
+                }
+            }
+
             let concrete_value = Box::new(NonCopy::new());
-            let ref_value = &concrete_value;
+            let ref_value = &*concrete_value;
             let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted: Scoped<dyn Access + Sync> = Scoped::map(guard.lift(), |value| value as _);
+            assert_eq!(guard.lifted_count(), 1);
+            let result = std::thread::spawn(move || lifted.access()).join().unwrap();
+            assert_eq!(result, 1.0);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn borrowing_type() {
+            struct Foo<'b> {
+                name: &'b str,
+                numbers: &'b [u32],
+            }
+
+            let name = String::from("one");
+            let numbers = vec![1, 2, 3];
+            let foo = Foo {
+                name: &name,
+                numbers: &numbers,
+            };
+            // `Foo<'b>` is not `'static`, but `str` and `[u32]` are
+            let guard = unsafe { ScopedGuard::new_tuple((foo.name, foo.numbers)) };
             let lifted = guard.lift();
-            lifted.access_value();
+            std::thread::spawn(move || {
+                let (name, numbers) = lifted.get();
+                assert_eq!(name, "one");
+                assert_eq!(numbers.iter().sum::<u32>(), 6);
+            })
+            .join()
+            .unwrap();
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn eq_value() {
+            let concrete_value = Box::new(2.0);
+            let guard = unsafe { ScopedGuard::new(&*concrete_value) };
+            let lifted = guard.lift();
+            assert_eq!(lifted, 2.0);
+            assert_eq!(lifted, &2.0);
+            assert_ne!(lifted, 1.0);
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        #[cfg(feature = "serde")]
+        fn serialize() {
+            #[derive(serde::Serialize)]
+            struct Config {
+                name: String,
+                threads: usize,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Snapshot {
+                config: Scoped<Config>,
+            }
+
+            let config = Config {
+                name: String::from("server"),
+                threads: 4,
+            };
+            let guard = unsafe { ScopedGuard::new(&config) };
+            let lifted = guard.lift();
+            assert_eq!(
+                serde_json::to_string(&lifted).unwrap(),
+                serde_json::to_string(&config).unwrap()
+            );
+            let snapshot = Snapshot { config: lifted };
+            assert_eq!(
+                serde_json::to_string(&snapshot).unwrap(),
+                r#"{"config":{"name":"server","threads":4}}"#
+            );
+            std::mem::drop(snapshot);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn cmp_value() {
+            let sorted = [1, 3, 5, 7];
+            let needle = 5;
+            let guard = unsafe { ScopedGuard::new(&needle) };
+            let lifted = guard.lift();
+            assert!(lifted > 3);
+            assert!(lifted < sorted.last().unwrap());
+            assert_eq!(
+                sorted.binary_search_by(|element| lifted.partial_cmp(element).unwrap().reverse()),
+                Ok(2)
+            );
+            let missing = 4;
+            let guard_missing = unsafe { ScopedGuard::new(&missing) };
+            let lifted_missing = guard_missing.lift();
+            assert_eq!(
+                sorted.binary_search_by(|element| {
+                    lifted_missing.partial_cmp(element).unwrap().reverse()
+                }),
+                Err(2)
+            );
+            std::mem::drop((lifted, lifted_missing));
+            std::mem::drop((guard, guard_missing));
+        }
+
+        #[test]
+        fn slice_get() {
+            let values = [NonCopy::new(), NonCopy::new(), NonCopy::new()];
+            let guard = unsafe { ScopedGuard::new(&values[..]) };
+            let lifted = guard.lift();
+            let handles: Vec<_> = (0..3)
+                .map(|index| {
+                    let element = Scoped::get(&lifted, index).unwrap();
+                    assert!(std::ptr::eq(&*element, &values[index]));
+                    std::thread::spawn(move || {
+                        std::thread::sleep(Duration::from_millis(10));
+                        element.access_value();
+                    })
+                })
+                .collect();
+            assert!(Scoped::get(&lifted, 3).is_none());
+            // The slice method is not shadowed
+            assert!(lifted.get(3).is_none());
+            std::mem::drop(lifted);
+            for handle in handles {
+                handle.join().unwrap();
+            }
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        #[should_panic]
+        fn slice_get_dangling() {
+            let values = [NonCopy::new(), NonCopy::new()];
+            let guard = unsafe { ScopedGuard::new(&values[..]) };
+            let element = Scoped::get(&guard.lift(), 1).unwrap();
+            // Keep the count non-zero without a `Scoped` outliving the guard
+            std::mem::forget(element);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn as_deref() {
+            let concrete_value = Box::new(1.0);
+            let guard = unsafe { ScopedGuard::new(&concrete_value) };
+            let lifted = guard.lift();
+            let inner: &f64 = Scoped::as_deref(&lifted);
+            assert_eq!(*inner, 1.0);
+            let name = Some(String::from("one"));
+            let guard_name = unsafe { ScopedGuard::new(&name) };
+            let lifted_name = guard_name.lift();
+            // The method of the underlying value is not shadowed
+            assert_eq!(lifted_name.as_deref(), Some("one"));
+            std::mem::drop((lifted, lifted_name));
+            std::mem::drop((guard, guard_name));
+        }
+
+        #[test]
+        fn leak_static() {
+            static VALUE: &str = "static";
+            let guard = unsafe { ScopedGuard::new(&VALUE) };
+            let lifted = guard.lift();
+            let kept = lifted.clone();
+            let value = unsafe { Scoped::leak_static(lifted) };
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(kept);
+            assert_eq!(guard.lifted_count(), 0);
+            // Does not abort, since the leaked reference no longer counts
+            std::mem::drop(guard);
+            assert_eq!(*value, "static");
+        }
+
+        #[tokio::test(flavor = "current_thread")]
+        async fn ref_cell_spawn_local() {
+            let concrete_value = RefCell::new(1);
+            let guard = unsafe { ScopedGuard::new(&concrete_value) };
+            let lifted = guard.lift();
+            tokio::task::LocalSet::new()
+                .run_until(async move {
+                    let tasks: Vec<_> = (0..4)
+                        .map(|_| {
+                            let lifted = lifted.clone();
+                            tokio::task::spawn_local(async move {
+                                tokio::task::yield_now().await;
+                                *Scoped::borrow_mut(&lifted) += 1;
+                            })
+                        })
+                        .collect();
+                    for task in tasks {
+                        task.await.unwrap();
+                    }
+                    assert_eq!(*Scoped::borrow(&lifted), 5);
+                })
+                .await;
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.into_inner(), 5);
+        }
+
+        #[test]
+        fn ref_cell_borrow_with_trait_in_scope() {
+            #[allow(unused_imports)]
+            use std::borrow::Borrow;
+            let concrete_value = RefCell::new(vec![1]);
+            let guard = unsafe { ScopedGuard::new(&concrete_value) };
+            let lifted = guard.lift();
+            Scoped::borrow_mut(&lifted).push(2);
+            let cloned = lifted.clone();
+            let borrowed = Scoped::borrow(&lifted);
+            assert_eq!(*Scoped::borrow(&cloned), [1, 2]);
+            // The borrow flag is shared by all handles, since they point to the same `RefCell`
+            assert!(cloned.try_borrow_mut().is_err());
+            std::mem::drop(borrowed);
+            assert!(cloned.try_borrow_mut().is_ok());
+            std::mem::drop((lifted, cloned));
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn ptr_eq() {
+            let concrete_value = Box::new(1.0);
+            let ref_value = &concrete_value;
+            let guard1 = unsafe { ScopedGuard::new(ref_value) };
+            let guard2 = unsafe { ScopedGuard::new(ref_value) };
+            let lifted1 = guard1.lift();
+            let lifted2 = guard1.lift();
+            let lifted3 = guard2.lift();
+            assert!(Scoped::ptr_eq(&lifted1, &lifted2));
+            assert!(Scoped::ptr_eq(&lifted1, &lifted1.clone()));
+            assert!(!Scoped::ptr_eq(&lifted1, &lifted3));
+            assert_eq!(lifted1, lifted3, "the values are still equal");
+            std::mem::drop((lifted1, lifted2, lifted3));
+            std::mem::drop(guard1);
+            std::mem::drop(guard2);
+        }
+
+        #[test]
+        fn guard_is_sole_remaining() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard = unsafe { ScopedGuard::new(&concrete_value) };
+            let lifted = guard.lift();
+            assert!(Scoped::guard_is_sole_remaining(&lifted));
+            let cloned = lifted.clone();
+            assert_eq!(guard.lifted_count(), 2);
+            assert!(!Scoped::guard_is_sole_remaining(&lifted));
             std::mem::drop(lifted);
+            assert_eq!(guard.lifted_count(), 1);
+            assert!(Scoped::guard_is_sole_remaining(&cloned));
+            std::mem::drop(cloned);
             std::mem::drop(guard);
+
+            // Converted from a `ScopedPin`, the other `ScopedPin` of the pinned guard count too
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let pinned = guard.lift();
+            let lifted = ScopedPin::into_arc(guard.lift());
+            assert!(!Scoped::guard_is_sole_remaining(&lifted));
+            std::mem::drop(pinned);
+            assert!(Scoped::guard_is_sole_remaining(&lifted));
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
         }
 
         #[tokio::test]
@@ -198,9 +3085,41 @@ mod tests {
             .unwrap();
             std::mem::drop(guard);
         }
+
+        #[test]
+        // smol uses syscalls Miri does not support
+        #[cfg_attr(miri, ignore)]
+        fn smol_valid() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard = unsafe { ScopedGuard::new(ref_value) };
+            let lifted = guard.lift();
+            smol::block_on(smol::spawn(async move {
+                smol::future::yield_now().await;
+                lifted.access_value();
+            }));
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn send_sync() {
+            // What multithreaded runtimes require of the futures they spawn
+            fn assert_send_sync<T: Send + Sync + 'static>() {}
+            assert_send_sync::<Scoped<NonCopy>>();
+            assert_send_sync::<Scoped<dyn Fn() + Sync>>();
+            // `Send` is enough for the value behind a `Mutex` to be `Sync`
+            assert_send_sync::<Scoped<std::sync::Mutex<std::cell::Cell<f64>>>>();
+            assert_send_sync::<super::super::WeakScoped<NonCopy>>();
+            // Sending the guard only sends a reference to the value. See `tests/ui/not_send_sync.rs`
+            // for the types that must not be `Send` or `Sync`.
+            fn assert_send_sync_guard<T: Send + Sync>() {}
+            assert_send_sync_guard::<ScopedGuard<'_, NonCopy>>();
+        }
     }
 
-    #[cfg(test)]
+    // These tests exhibit undefined behavior on purpose, so they are skipped under Miri unless
+    // explicitly requested with `--cfg ub_tests`.
+    #[cfg(any(not(miri), ub_tests))]
     mod ub_tests {
         use super::super::ScopedGuard;
         use super::NonCopy;
@@ -225,6 +3144,7 @@ mod tests {
         }
 
         #[tokio::test]
+        #[allow(clippy::let_and_return)]
         async fn async_undefined_behavior() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
@@ -321,6 +3241,7 @@ mod tests {
         }
 
         #[tokio::test]
+        #[allow(clippy::let_and_return)]
         async fn async_undefined_behavior() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
@@ -340,5 +3261,117 @@ mod tests {
             let result = fut.await.unwrap();
             assert!(result.is_ok(), "Forgetting a reference has no effect");
         }
+
+        #[tokio::test]
+        async fn scoped_all() {
+            let number = Box::new(NonCopy::new());
+            let name = String::from("one");
+            let values = vec![1, 2, 3];
+            let (number_guard, name_guard, values_guard) = scoped_all!(&number, &name, &values);
+            let number_lifted = number_guard.lift();
+            let name_lifted = name_guard.lift();
+            let values_lifted = values_guard.lift();
+            tokio::spawn(async move {
+                number_lifted.access_value();
+                assert_eq!(*name_lifted, "one");
+                assert_eq!(values_lifted.iter().sum::<i32>(), 6);
+            })
+            .await
+            .unwrap();
+            assert_eq!(number_guard.lifted_count(), 0);
+            assert_eq!(name_guard.lifted_count(), 0);
+            assert_eq!(values_guard.lifted_count(), 0);
+        }
+
+        #[test]
+        fn scoped_lift() {
+            let concrete_value = Box::new(NonCopy::new());
+            scoped_lift!(lifted, &concrete_value);
+            let cloned = lifted.clone();
+            std::thread::spawn(move || cloned.access_value())
+                .join()
+                .unwrap();
+            lifted.access_value();
+        }
+
+        #[test]
+        fn scoped_lift_checks_on_drop() {
+            let _lock = crate::utils::HANDLER_LOCK
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            let concrete_value = Box::new(NonCopy::new());
+            let result = std::panic::catch_unwind(|| {
+                scoped_lift!(lifted, &concrete_value);
+                // Keeps the count up when the hidden guard is dropped at the end of this scope
+                std::mem::forget(lifted);
+            });
+            let Err(payload) = result else {
+                panic!("expected the hidden guard to detect the leak");
+            };
+            let message = payload.downcast_ref::<String>().unwrap();
+            assert!(message.contains("1 lifted reference still exists"), "{message}");
+        }
+
+        #[test]
+        fn scoped_all_independent() {
+            let first = Box::new(NonCopy::new());
+            let second = Box::new(NonCopy::new());
+            let (first_guard, second_guard) = scoped_all!(&first, &second);
+            let lifted = first_guard.lift();
+            assert_eq!(first_guard.lifted_count(), 1);
+            assert_eq!(second_guard.lifted_count(), 0);
+            std::mem::drop(lifted);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "shuttle"))]
+mod shuttle_tests {
+    use super::ScopedGuard;
+    use crate::sync::shuttle_check;
+    use shuttle::sync::atomic::{AtomicUsize, Ordering};
+    use shuttle::thread;
+
+    const THREADS: usize = 3;
+
+    #[test]
+    fn lift_clone_drop_across_threads() {
+        shuttle_check(|| {
+            let concrete_value = AtomicUsize::new(0);
+            let guard = unsafe { ScopedGuard::new(&concrete_value) };
+            for _ in 0..THREADS {
+                let lifted = guard.lift();
+                thread::spawn(move || {
+                    let cloned = lifted.clone();
+                    lifted.fetch_add(1, Ordering::Relaxed);
+                    std::mem::drop(lifted);
+                    thread::yield_now();
+                    cloned.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+            // Not joined, so the guard only relies on the count of the threads' handles
+            while guard.lifted_count() != 0 {
+                thread::yield_now();
+            }
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.load(Ordering::Relaxed), 2 * THREADS);
+        });
+    }
+
+    #[test]
+    fn join_waits_for_last_drop() {
+        shuttle_check(|| {
+            let concrete_value = AtomicUsize::new(0);
+            let guard = unsafe { ScopedGuard::new(&concrete_value) };
+            for _ in 0..THREADS {
+                let lifted = guard.lift();
+                thread::spawn(move || {
+                    lifted.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+            // Parks with `notify`, so a lost wakeup is reported as a deadlock
+            guard.join();
+            assert_eq!(concrete_value.load(Ordering::Relaxed), THREADS);
+        });
     }
 }
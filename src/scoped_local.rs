@@ -0,0 +1,351 @@
+use core::borrow::Borrow;
+use core::cell::Cell;
+use core::fmt;
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::{marker::PhantomData, mem, ops::Deref};
+
+use crate::utils;
+
+/// A safe way to create a [`ScopedPinGuardLocal`].
+/// ```rust
+/// use scoped_static::scoped_pin_local;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let concrete_value = Box::new(1.0);
+///     let ref_value = &concrete_value;
+///     scoped_pin_local!(guard, ref_value);
+///     let lifted = guard.lift();
+///     tokio::task::LocalSet::new()
+///         .run_until(async move {
+///             tokio::task::spawn_local(async move {
+///                 // Lifted is 'static so it can be moved into this closure that needs 'static
+///                 assert_eq!(**lifted, 1.0);
+///             })
+///             .await
+///             .unwrap();
+///         })
+///         .await;
+///    // `guard` is dropped here
+/// }
+/// ```
+#[macro_export]
+macro_rules! scoped_pin_local {
+    ($guard_ident:ident, $ref_value:expr) => {
+        let $guard_ident = {
+            let ref_value = $ref_value;
+            unsafe { $crate::ScopedPinGuardLocal::new(ref_value) }
+        };
+        let $guard_ident = unsafe { ::core::pin::Pin::new_unchecked(&$guard_ident) };
+    };
+}
+
+/// Like [`crate::ScopedPinGuard`], but for a guard and lifted references that all stay on one
+/// thread. The counter is a plain [`Cell`] instead of an atomic, so lifting, cloning and dropping
+/// are cheaper. The derived [`ScopedLocal`] are neither `Send` nor `Sync`, e.g. for
+/// `tokio::task::spawn_local`:
+///
+/// ```rust,compile_fail
+/// use scoped_static::scoped_pin_local;
+///
+/// let value = 1.0;
+/// scoped_pin_local!(guard, &value);
+/// let lifted = guard.lift();
+/// std::thread::spawn(move || assert_eq!(*lifted, 1.0));
+/// ```
+///
+/// The same rules as for [`crate::ScopedPinGuard`] apply. It aborts if dropped while any derived
+/// [`ScopedLocal`] exist, and it may cause undefined behavior to leak/forget this value.
+///
+/// See [`scoped_pin_local`] macro for a safe way to create.
+#[derive(Debug)]
+pub struct ScopedPinGuardLocal<'a, T: 'static> {
+    value: &'static T,
+    counter: Cell<usize>,
+    _scope: PhantomData<&'a ()>,
+    _unpinnable: PhantomPinned,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl<'a, T: 'static> ScopedPinGuardLocal<'a, T> {
+    /// Creates a new [`ScopedPinGuardLocal`]. See [`scoped_pin_local`] for a safe way to create.
+    ///
+    /// # Safety
+    ///
+    /// The returned guard must be pinned before lifting and must not be leaked/forgotten.
+    /// See [`ScopedPinGuardLocal`].
+    pub const unsafe fn new(value: &'a T) -> Self {
+        let value = unsafe { mem::transmute::<&'a T, &'static T>(value) };
+        ScopedPinGuardLocal {
+            value,
+            counter: Cell::new(0),
+            _scope: PhantomData,
+            _unpinnable: PhantomPinned,
+            _not_send: PhantomData,
+        }
+    }
+
+    /// Lifts this reference with lifetime `'a` into `'static` and relies on runtime
+    /// checks to ensure safety.
+    pub fn lift(self: Pin<&Self>) -> ScopedLocal<T> {
+        increment(&self.counter);
+        ScopedLocal {
+            value: self.value,
+            counter: NonNull::from_ref(&self.counter),
+        }
+    }
+
+    /// The number of [`ScopedLocal`] derived from this guard that currently exist. This guard can
+    /// only be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.counter.get()
+    }
+}
+
+impl<'a, T> Deref for ScopedPinGuardLocal<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<'a, T: 'static> Drop for ScopedPinGuardLocal<'a, T> {
+    fn drop(&mut self) {
+        let count = self.counter.get();
+        if count != 0 {
+            utils::abort(count);
+        }
+    }
+}
+
+/// Counts a new [`ScopedLocal`]. Never wraps around to zero, e.g. when forgetting [`ScopedLocal`] in
+/// a loop, so the guard still aborts if dropped after unwinding.
+fn increment(counter: &Cell<usize>) {
+    let count = counter
+        .get()
+        .checked_add(1)
+        .expect("too many `ScopedLocal` derived from one guard");
+    counter.set(count);
+}
+
+/// A reference derived from a [`ScopedPinGuardLocal`]. The lifetime of the underlying
+/// value has been lifted to `'static`. See [`ScopedPinGuardLocal`] for more info.
+pub struct ScopedLocal<T: 'static> {
+    value: &'static T,
+    // Also makes this neither `Send` nor `Sync`
+    counter: NonNull<Cell<usize>>,
+}
+
+impl<T: 'static> ScopedLocal<T> {
+    /// Formats the underlying value, like `ScopedLocal(value)`. The [`Debug`](fmt::Debug) of
+    /// [`ScopedLocal`] itself only formats the address.
+    ///
+    /// This is an associated function that needs to be used as `ScopedLocal::debug_value(...)`, so
+    /// that it does not conflict with a method of the same name on the underlying value.
+    pub fn debug_value(this: &Self) -> impl fmt::Debug + '_
+    where
+        T: fmt::Debug,
+    {
+        utils::DebugValue {
+            name: "ScopedLocal",
+            value: this.value,
+        }
+    }
+}
+
+impl<T: 'static> Deref for ScopedLocal<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T: 'static> AsRef<T> for ScopedLocal<T> {
+    fn as_ref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: 'static> Borrow<T> for ScopedLocal<T> {
+    fn borrow(&self) -> &T {
+        self.value
+    }
+}
+
+/// Formats the address of the underlying value, without reading the value, like
+/// [`crate::ScopedPin`]. See [`ScopedLocal::debug_value`] to format the value.
+impl<T: 'static> fmt::Debug for ScopedLocal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedLocal")
+            .field("ptr", &(self.value as *const T))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static + fmt::Display> fmt::Display for ScopedLocal<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.value, f)
+    }
+}
+
+impl<T: 'static> Clone for ScopedLocal<T> {
+    fn clone(&self) -> Self {
+        increment(unsafe { self.counter.as_ref() });
+        ScopedLocal {
+            value: self.value,
+            counter: self.counter,
+        }
+    }
+}
+
+impl<T: 'static> Drop for ScopedLocal<T> {
+    fn drop(&mut self) {
+        let counter = unsafe { self.counter.as_ref() };
+        counter.set(counter.get() - 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    struct NonCopy(f32);
+
+    impl NonCopy {
+        pub fn new() -> Self {
+            NonCopy(1.0)
+        }
+        pub fn access_value(&self) {
+            assert_eq!(self.0, 1.0, "If these values are not equal it signals UB");
+        }
+    }
+
+    #[cfg(test)]
+    mod normal_tests {
+        use super::super::{ScopedLocal, ScopedPinGuardLocal};
+        use super::NonCopy;
+
+        #[test]
+        #[should_panic]
+        fn dangling() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn valid() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn overflow() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            // As if `usize::MAX` handles were forgotten
+            guard.counter.set(usize::MAX);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| guard.lift()));
+            assert!(result.is_err(), "expected a panic instead of wrapping around");
+            assert_eq!(guard.lifted_count(), usize::MAX);
+            guard.counter.set(0);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn lifted_count() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            assert_eq!(guard.lifted_count(), 0);
+            let lifted1 = guard.lift();
+            let lifted2 = lifted1.clone();
+            assert_eq!(guard.lifted_count(), 2);
+            std::mem::drop(lifted1);
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(lifted2);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn debug() {
+            /// Fails the test if its `Debug` is ever called.
+            struct Unreadable;
+
+            impl std::fmt::Debug for Unreadable {
+                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    panic!("the value must not be read")
+                }
+            }
+
+            let value = Box::new(Unreadable);
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(&*value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let debug = format!("{lifted:?}");
+            assert_eq!(debug, format!("ScopedLocal {{ ptr: {:p}, .. }}", &*lifted));
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+
+            let value = Box::new(1.0);
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(&*value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!(format!("{:?}", ScopedLocal::debug_value(&lifted)), "ScopedLocal(1.0)");
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[tokio::test(flavor = "current_thread")]
+        async fn spawn_local() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuardLocal::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            tokio::task::LocalSet::new()
+                .run_until(async move {
+                    tokio::task::spawn_local(async move {
+                        tokio::task::yield_now().await;
+                        lifted.access_value();
+                    })
+                    .await
+                    .unwrap();
+                })
+                .await;
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard_unpinned);
+        }
+    }
+
+    #[cfg(test)]
+    mod macro_tests {
+        use super::NonCopy;
+
+        #[test]
+        fn valid() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            scoped_pin_local!(guard, ref_value);
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+        }
+    }
+}
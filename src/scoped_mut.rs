@@ -0,0 +1,639 @@
+use alloc::sync::Arc;
+use core::fmt;
+use core::ptr::NonNull;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use core::{
+    future::Future,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::utils;
+
+/// A safe way to create a [`ScopedMutGuard`].
+/// ```rust
+/// use scoped_static::scoped_mut;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut concrete_value = Box::new(1.0);
+///     let ref_value = &mut concrete_value;
+///     let guard = scoped_mut!(ref_value);
+///     let mut lifted = guard.lift_mut().unwrap();
+///     tokio::spawn(async move {
+///         // Lifted is 'static so it can be moved into this closure that needs 'static
+///         **lifted += 1.0;
+///         // `lifted` is dropped here
+///     })
+///     .await
+///     .unwrap();
+///    // `guard` is dropped here
+/// }
+/// ```
+#[macro_export]
+macro_rules! scoped_mut {
+    ($ref_value:expr) => {
+        &mut {
+            let ref_value = $ref_value;
+            unsafe { $crate::ScopedMutGuard::new(ref_value) }
+        }
+    };
+}
+
+/// A mutable reference with lifetime `'a` that can be lifted to an exclusive reference with a `'static`
+/// lifetime ([`ScopedMut`]). At most one [`ScopedMut`] may exist at a time. Runtime checks are used to
+/// ensure that no derived [`ScopedMut`] exists when this [`ScopedMutGuard`] is dropped.
+///
+/// ```rust
+/// use scoped_static::ScopedMutGuard;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut concrete_value = Box::new(1.0);
+///     let ref_value = &mut concrete_value;
+///     let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+///     let mut lifted = guard.lift_mut().unwrap();
+///     tokio::spawn(async move {
+///         // Lifted is 'static so it can be moved into this closure that needs 'static
+///         **lifted += 1.0;
+///         // `lifted` is dropped here
+///     })
+///     .await
+///     .unwrap();
+///     std::mem::drop(guard);
+///     assert_eq!(*concrete_value, 2.0);
+/// }
+/// ```
+///
+/// If a [`ScopedMutGuard`] is dropped while the derived [`ScopedMut`] exists, then it will abort the whole
+/// program (instead of panic). This is because [`ScopedMut`] could exist on another thread and be unaffected
+/// by the panic or the panic could be recovered from. This could lead to undefined behavior.
+///
+/// UNDEFINED BEHAVIOR: It may cause undefined behavior to leak/forget this value. Since
+/// the `Drop` code must run to prevent undefined behavior.
+/// e.g. [`std::mem::forget`], [`std::mem::ManuallyDrop`], or Rc cycles, etc.
+///
+/// See [`scoped_mut`] macro for a safe way to create.
+#[derive(Debug)]
+pub struct ScopedMutGuard<'a, T: 'static + ?Sized> {
+    value: NonNull<T>,
+    /// The number of [`ScopedMut`] that exist. Counted explicitly instead of by the strong count of
+    /// the `Arc`, so holding the `Arc` for anything else does not count as a lift.
+    lifted: Arc<AtomicUsize>,
+    _scope: PhantomData<&'a mut T>,
+}
+
+unsafe impl<'a, T: 'static + Send + ?Sized> Send for ScopedMutGuard<'a, T> {}
+unsafe impl<'a, T: 'static + Sync + ?Sized> Sync for ScopedMutGuard<'a, T> {}
+
+impl<'a, T: 'static + ?Sized> ScopedMutGuard<'a, T> {
+    /// Creates a new [`ScopedMutGuard`]. See [`scoped_mut`] for a safe way to create.
+    ///
+    /// # Safety
+    ///
+    /// The returned guard must not be leaked/forgotten. See [`ScopedMutGuard`].
+    pub unsafe fn new(value: &'a mut T) -> Self {
+        let value = unsafe { mem::transmute::<&'a mut T, &'static mut T>(value) };
+        ScopedMutGuard {
+            value: NonNull::from(value),
+            lifted: Arc::new(AtomicUsize::new(0)),
+            _scope: PhantomData,
+        }
+    }
+
+    /// Lifts this mutable reference with lifetime `'a` into `'static` and relies on runtime
+    /// checks to ensure safety. Returns `None` if a [`ScopedMut`] derived from this guard still exists.
+    pub fn lift_mut(&mut self) -> Option<ScopedMut<T>> {
+        // Acquire synchronizes with the drop of the previous `ScopedMut`, which may have been on
+        // another thread.
+        if self.lifted_count() != 0 {
+            return None;
+        }
+        Some(ScopedMut {
+            value: self.value,
+            lifted: Lifted::new(&self.lifted),
+            _invariant: PhantomData,
+        })
+    }
+
+    /// Returns a mutable reference to the underlying value if no [`ScopedMut`] derived from this
+    /// guard exists. Otherwise returns `None`, like [`Arc::get_mut`].
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        // Acquire synchronizes with the drop of the previous `ScopedMut`, as in `lift_mut`
+        if self.lifted_count() != 0 {
+            return None;
+        }
+        Some(unsafe { self.value.as_mut() })
+    }
+
+    /// The number of [`ScopedMut`] derived from this guard that currently exist. More than one only
+    /// after [`ScopedMut::map_split`], each for a disjoint part. This guard can only be dropped
+    /// without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.lifted.load(Ordering::Acquire)
+    }
+}
+
+impl<'a, T: 'static + ?Sized> Drop for ScopedMutGuard<'a, T> {
+    fn drop(&mut self) {
+        // Acquire synchronizes with the drop of the `ScopedMut`, so its writes are visible once `'a`
+        // resumes.
+        let lifted = self.lifted_count();
+        if lifted != 0 {
+            utils::abort(lifted);
+        }
+    }
+}
+
+/// Counts a [`ScopedMut`] towards its guard for as long as it exists.
+struct Lifted(Arc<AtomicUsize>);
+
+impl Lifted {
+    fn new(count: &Arc<AtomicUsize>) -> Self {
+        // Relaxed is enough, like `Arc::clone`. The guard only observes it through the `ScopedMut`.
+        count.fetch_add(1, Ordering::Relaxed);
+        Lifted(Arc::clone(count))
+    }
+}
+
+impl Drop for Lifted {
+    fn drop(&mut self) {
+        // Release so all writes through the `ScopedMut` happen before the guard observes the
+        // decrement.
+        self.0.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// An exclusive reference derived from a [`ScopedMutGuard`]. The lifetime of the underlying
+/// value has been lifted to `'static`. See [`ScopedMutGuard`] for more info.
+pub struct ScopedMut<T: 'static + ?Sized> {
+    value: NonNull<T>,
+    lifted: Lifted,
+    // Invariant like `&mut T`, so a `ScopedMut` cannot be coerced to write a shorter lived value.
+    _invariant: PhantomData<fn(T) -> T>,
+}
+
+unsafe impl<T: 'static + Send + ?Sized> Send for ScopedMut<T> {}
+unsafe impl<T: 'static + Sync + ?Sized> Sync for ScopedMut<T> {}
+
+impl<T: 'static + ?Sized> ScopedMut<T> {
+    /// Makes a new [`ScopedMut`] for a component of the underlying value, like
+    /// `MappedMutexGuard`. `this` is consumed, so the returned [`ScopedMut`] is still the only
+    /// exclusive reference, and it keeps the originating [`ScopedMutGuard`] from being dropped.
+    ///
+    /// This is an associated function that needs to be used as `ScopedMut::map_mut(...)`, so that
+    /// it does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped_mut, ScopedMut};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut value = (1, String::from("one"));
+    ///     {
+    ///         let guard = scoped_mut!(&mut value);
+    ///         let mut name = ScopedMut::map_mut(guard.lift_mut().unwrap(), |(_, name)| name);
+    ///         tokio::spawn(async move { name.push_str(" two") })
+    ///             .await
+    ///             .unwrap();
+    ///     }
+    ///     assert_eq!(value.1, "one two");
+    /// }
+    /// ```
+    pub fn map_mut<U: 'static + ?Sized, F: FnOnce(&mut T) -> &mut U>(
+        this: Self,
+        f: F,
+    ) -> ScopedMut<U> {
+        let ScopedMut {
+            mut value, lifted, ..
+        } = this;
+        let value = NonNull::from(f(unsafe { value.as_mut() }));
+        ScopedMut {
+            value,
+            lifted,
+            _invariant: PhantomData,
+        }
+    }
+
+    /// Splits a [`ScopedMut`] into two for disjoint components of the underlying value, like
+    /// `RefMut::map_split`. The components cannot alias, since `f` borrows both of them from the
+    /// same exclusive reference. Both keep the originating [`ScopedMutGuard`] from lifting again or
+    /// being dropped until they are dropped.
+    ///
+    /// This is an associated function that needs to be used as `ScopedMut::map_split(...)`, so that
+    /// it does not conflict with a method of the same name on the underlying value.
+    pub fn map_split<U, V, F>(this: Self, f: F) -> (ScopedMut<U>, ScopedMut<V>)
+    where
+        U: 'static + ?Sized,
+        V: 'static + ?Sized,
+        F: FnOnce(&mut T) -> (&mut U, &mut V),
+    {
+        let ScopedMut {
+            mut value, lifted, ..
+        } = this;
+        let (first, second) = f(unsafe { value.as_mut() });
+        let first = ScopedMut {
+            value: NonNull::from(first),
+            lifted: Lifted::new(&lifted.0),
+            _invariant: PhantomData,
+        };
+        let second = ScopedMut {
+            value: NonNull::from(second),
+            lifted,
+            _invariant: PhantomData,
+        };
+        (first, second)
+    }
+
+    /// Formats the underlying value, like `ScopedMut(value)`. The [`Debug`](fmt::Debug) of
+    /// [`ScopedMut`] itself only formats the address.
+    ///
+    /// This is an associated function that needs to be used as `ScopedMut::debug_value(...)`, so
+    /// that it does not conflict with a method of the same name on the underlying value.
+    pub fn debug_value(this: &Self) -> impl fmt::Debug + '_
+    where
+        T: fmt::Debug,
+    {
+        utils::DebugValue {
+            name: "ScopedMut",
+            value: &**this,
+        }
+    }
+}
+
+impl<T: 'static> ScopedMut<[T]> {
+    /// Splits a lifted slice into two at `mid`, like `<[T]>::split_at_mut`, e.g. to fill each half
+    /// on a different thread. Panics if `mid > len`. See [`ScopedMut::map_split`].
+    ///
+    /// This is an associated function that needs to be used as `ScopedMut::split_at_mut(...)`, since
+    /// the method of the slice is found first.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped_mut, ScopedMut};
+    ///
+    /// let mut values = [0; 8];
+    /// {
+    ///     let guard = scoped_mut!(&mut values[..]);
+    ///     let lifted = guard.lift_mut().unwrap();
+    ///     let (mut first, mut second) = ScopedMut::split_at_mut(lifted, 4);
+    ///     let first = std::thread::spawn(move || first.fill(1));
+    ///     let second = std::thread::spawn(move || second.fill(2));
+    ///     first.join().unwrap();
+    ///     second.join().unwrap();
+    /// }
+    /// assert_eq!(values, [1, 1, 1, 1, 2, 2, 2, 2]);
+    /// ```
+    pub fn split_at_mut(this: Self, mid: usize) -> (ScopedMut<[T]>, ScopedMut<[T]>) {
+        ScopedMut::map_split(this, |slice| slice.split_at_mut(mid))
+    }
+}
+
+impl<T: 'static + ?Sized> Deref for ScopedMut<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T: 'static + ?Sized> DerefMut for ScopedMut<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.value.as_mut() }
+    }
+}
+
+/// Polls the underlying future, so a lifted future can be moved into a task and awaited there.
+///
+/// Only `Unpin` futures are supported, since the underlying value may be moved once the
+/// [`ScopedMutGuard`] is dropped. Pin others on the heap first, e.g. with `Box::pin`.
+///
+/// ```rust
+/// use scoped_static::scoped_mut;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let mut future = Box::pin(async { 1 });
+///     let guard = scoped_mut!(&mut future);
+///     let lifted = guard.lift_mut().unwrap();
+///     assert_eq!(tokio::spawn(lifted).await.unwrap(), 1);
+/// }
+/// ```
+impl<F: 'static + Future + Unpin> Future for ScopedMut<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut **self).poll(cx)
+    }
+}
+
+/// Formats the address of the underlying value, without reading the value, like
+/// [`crate::ScopedPin`]. See [`ScopedMut::debug_value`] to format the value.
+impl<T: 'static + ?Sized> fmt::Debug for ScopedMut<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedMut")
+            .field("ptr", &self.value.as_ptr())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    struct Counter(u32);
+
+    impl Counter {
+        pub fn new() -> Self {
+            Counter(0)
+        }
+        pub fn increment(&mut self) {
+            self.0 += 1;
+        }
+    }
+
+    #[cfg(test)]
+    mod normal_tests {
+        use super::super::{ScopedMut, ScopedMutGuard};
+        use super::Counter;
+
+        #[test]
+        fn dangling() {
+            let mut concrete_value = Box::new(Counter::new());
+            let ref_value = &mut concrete_value;
+            let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+            let mut lifted = guard.lift_mut().unwrap();
+            lifted.increment();
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                std::mem::drop(guard);
+            }));
+            assert!(
+                result.is_err(),
+                "expected panic when dropping ScopedMutGuard with an alive ScopedMut"
+            );
+        }
+
+        #[test]
+        fn valid() {
+            let mut concrete_value = Box::new(Counter::new());
+            let ref_value = &mut concrete_value;
+            let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+            let mut lifted = guard.lift_mut().unwrap();
+            lifted.increment();
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.0, 1);
+        }
+
+        #[test]
+        fn single_lift() {
+            let mut concrete_value = Box::new(Counter::new());
+            let ref_value = &mut concrete_value;
+            let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+            let lifted = guard.lift_mut().unwrap();
+            assert!(
+                guard.lift_mut().is_none(),
+                "a second ScopedMut must not be handed out while one exists"
+            );
+            std::mem::drop(lifted);
+            let lifted = guard.lift_mut();
+            assert!(
+                lifted.is_some(),
+                "lifting again after the drop should succeed"
+            );
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
+
+        #[test]
+        fn lifted_count() {
+            let mut concrete_value = Box::new([Counter::new(), Counter::new()]);
+            let ref_value = &mut *concrete_value;
+            let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+            assert_eq!(guard.lifted_count(), 0);
+            let lifted = guard.lift_mut().unwrap();
+            assert_eq!(guard.lifted_count(), 1);
+            // Moves the count instead of cloning it
+            let lifted = ScopedMut::map_mut(lifted, |pair| pair.as_mut_slice());
+            assert_eq!(guard.lifted_count(), 1);
+            // Clones the count for the second half
+            let (mut first, mut second) = ScopedMut::split_at_mut(lifted, 1);
+            assert_eq!(guard.lifted_count(), 2);
+            first[0].increment();
+            std::mem::drop(first);
+            assert_eq!(guard.lifted_count(), 1);
+            second[0].increment();
+            std::mem::drop(second);
+            assert_eq!(guard.lifted_count(), 0);
+            assert!(guard.get_mut().is_some());
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.map(|counter| counter.0), [1, 1]);
+        }
+
+        #[test]
+        fn lifted_count_ignores_other_arc_clones() {
+            let mut concrete_value = Box::new(Counter::new());
+            let mut guard = unsafe { ScopedMutGuard::new(&mut *concrete_value) };
+            // As if the guard shared its `Arc` internally for anything but a lift
+            let shared = std::sync::Arc::clone(&guard.lifted);
+            assert_eq!(guard.lifted_count(), 0);
+            assert!(guard.get_mut().is_some());
+            let mut lifted = guard.lift_mut().unwrap();
+            lifted.increment();
+            std::mem::drop(lifted);
+            assert_eq!(guard.lifted_count(), 0);
+            // Does not abort, since no `ScopedMut` exists
+            std::mem::drop(guard);
+            std::mem::drop(shared);
+            assert_eq!(concrete_value.0, 1);
+        }
+
+        #[test]
+        fn get_mut() {
+            let mut concrete_value = Box::new(Counter::new());
+            let ref_value = &mut concrete_value;
+            let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+            guard.get_mut().unwrap().increment();
+            let mut lifted = guard.lift_mut().unwrap();
+            assert!(
+                guard.get_mut().is_none(),
+                "the guard must not hand out a mutable reference while a ScopedMut exists"
+            );
+            lifted.increment();
+            std::mem::drop(lifted);
+            guard.get_mut().unwrap().increment();
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.0, 3);
+        }
+
+        #[tokio::test]
+        async fn async_valid() {
+            let mut concrete_value = Box::new(Counter::new());
+            let ref_value = &mut concrete_value;
+            let mut guard = unsafe { ScopedMutGuard::new(ref_value) };
+            let mut lifted = guard.lift_mut().unwrap();
+            tokio::spawn(async move {
+                lifted.increment();
+            })
+            .await
+            .unwrap();
+            let mut lifted = guard.lift_mut().unwrap();
+            tokio::spawn(async move {
+                lifted.increment();
+            })
+            .await
+            .unwrap();
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.0, 2);
+        }
+
+        #[test]
+        fn map_mut() {
+            let mut concrete_value = (Counter::new(), Counter::new());
+            let mut guard = unsafe { ScopedMutGuard::new(&mut concrete_value) };
+            let lifted = guard.lift_mut().unwrap();
+            let mut second = ScopedMut::map_mut(lifted, |(_, second)| second);
+            assert!(
+                guard.lift_mut().is_none(),
+                "the projection still holds the exclusive lift"
+            );
+            std::thread::spawn(move || {
+                second.increment();
+                second.increment();
+            })
+            .join()
+            .unwrap();
+            guard.get_mut().unwrap().0.increment();
+            std::mem::drop(guard);
+            assert_eq!(concrete_value.0.0, 1);
+            assert_eq!(concrete_value.1.0, 2);
+        }
+
+        #[test]
+        fn split_at_mut() {
+            let mut concrete_value = [0u32; 8];
+            let mut guard = unsafe { ScopedMutGuard::new(&mut concrete_value[..]) };
+            let lifted = guard.lift_mut().unwrap();
+            let (mut first, second) = ScopedMut::split_at_mut(lifted, 3);
+            let first_range = first.as_ptr_range();
+            let second_range = second.as_ptr_range();
+            assert_eq!(
+                first_range.end, second_range.start,
+                "the halves must not overlap"
+            );
+            assert_eq!((first.len(), second.len()), (3, 5));
+            assert!(
+                guard.lift_mut().is_none(),
+                "the halves still hold the exclusive lift"
+            );
+            let first = std::thread::spawn(move || first.fill(1));
+            let (mut second, mut third) = ScopedMut::split_at_mut(second, 2);
+            let second = std::thread::spawn(move || second.fill(2));
+            third.fill(3);
+            std::mem::drop(third);
+            first.join().unwrap();
+            second.join().unwrap();
+            guard.get_mut().unwrap()[0] += 1;
+            std::mem::drop(guard);
+            assert_eq!(concrete_value, [2, 1, 1, 2, 2, 3, 3, 3]);
+        }
+
+        #[test]
+        fn split_at_mut_dangling() {
+            let mut concrete_value = [0u32; 8];
+            let mut guard = unsafe { ScopedMutGuard::new(&mut concrete_value[..]) };
+            let lifted = guard.lift_mut().unwrap();
+            let (first, second) = ScopedMut::split_at_mut(lifted, 4);
+            std::mem::drop(first);
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                std::mem::drop(guard);
+            }));
+            assert!(
+                result.is_err(),
+                "expected panic when dropping ScopedMutGuard with one half still alive"
+            );
+            std::mem::forget(second);
+        }
+
+        #[test]
+        fn debug() {
+            /// Fails the test if its `Debug` is ever called.
+            struct Unreadable;
+
+            impl std::fmt::Debug for Unreadable {
+                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    panic!("the value must not be read")
+                }
+            }
+
+            let mut value = Box::new(Unreadable);
+            let mut guard = unsafe { ScopedMutGuard::new(&mut *value) };
+            let lifted = guard.lift_mut().unwrap();
+            let debug = format!("{lifted:?}");
+            assert_eq!(debug, format!("ScopedMut {{ ptr: {:p}, .. }}", &*lifted));
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+
+            let mut value = Box::new(1.0);
+            let mut guard = unsafe { ScopedMutGuard::new(&mut *value) };
+            let lifted = guard.lift_mut().unwrap();
+            assert_eq!(format!("{:?}", ScopedMut::debug_value(&lifted)), "ScopedMut(1.0)");
+            std::mem::drop(lifted);
+            std::mem::drop(guard);
+        }
+
+        #[tokio::test]
+        async fn future() {
+            let mut future = Box::pin(async {
+                tokio::task::yield_now().await;
+                1
+            });
+            let mut guard = unsafe { ScopedMutGuard::new(&mut future) };
+            let lifted = guard.lift_mut().unwrap();
+            // The test runtime is single threaded, so the task only runs once awaited
+            let handle = tokio::spawn(lifted);
+            assert!(
+                guard.lift_mut().is_none(),
+                "the future is still lifted while the task exists"
+            );
+            assert_eq!(handle.await.unwrap(), 1);
+            assert!(guard.get_mut().is_some());
+            std::mem::drop(guard);
+        }
+    }
+
+    #[cfg(test)]
+    mod macro_tests {
+        #![deny(dropping_references)]
+        #![deny(forgetting_references)]
+        use super::Counter;
+
+        #[test]
+        fn valid() {
+            let mut concrete_value = Box::new(Counter::new());
+            let ref_value = &mut concrete_value;
+            let guard = scoped_mut!(ref_value);
+            let mut lifted = guard.lift_mut().unwrap();
+            lifted.increment();
+            std::mem::drop(lifted);
+            #[allow(dropping_references)]
+            std::mem::drop(guard);
+        }
+
+        #[tokio::test]
+        async fn async_valid() {
+            let mut concrete_value = Box::new(Counter::new());
+            {
+                let ref_value = &mut concrete_value;
+                let guard = scoped_mut!(ref_value);
+                let mut lifted = guard.lift_mut().unwrap();
+                tokio::spawn(async move {
+                    lifted.increment();
+                })
+                .await
+                .unwrap();
+            }
+            assert_eq!(concrete_value.0, 1);
+        }
+    }
+}
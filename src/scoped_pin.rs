@@ -1,10 +1,20 @@
-use std::marker::PhantomPinned;
-use std::pin::Pin;
-use std::ptr::NonNull;
-use std::sync::atomic::AtomicUsize;
-use std::{marker::PhantomData, mem, ops::Deref};
+use core::borrow::Borrow;
+use core::cmp;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomPinned;
+use core::pin::Pin;
+use core::ptr::NonNull;
+use core::sync::atomic::Ordering;
+use core::{marker::PhantomData, mem, ops::Deref};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
-use crate::utils;
+#[cfg(feature = "alloc")]
+use crate::Scoped;
+use crate::lifts::{LiftId, Lifts};
+use crate::sync::{AtomicUsize, Drained, loom_const_fn};
+use crate::utils::{self, LeakError};
 
 /// A safe way to create a [`ScopedPinGuard`].
 /// ```rust
@@ -30,8 +40,11 @@ use crate::utils;
 #[macro_export]
 macro_rules! scoped_pin {
     ($guard_ident:ident, $ref_value:expr) => {
-        let mut $guard_ident = unsafe { $crate::ScopedPinGuard::new($ref_value) };
-        let $guard_ident = &mut unsafe { std::pin::Pin::new_unchecked(&mut $guard_ident) };
+        let $guard_ident = {
+            let ref_value = $ref_value;
+            unsafe { $crate::ScopedPinGuard::new(ref_value) }
+        };
+        let $guard_ident = unsafe { ::core::pin::Pin::new_unchecked(&$guard_ident) };
     };
 }
 
@@ -46,8 +59,8 @@ macro_rules! scoped_pin {
 /// async fn main() {
 ///     let concrete_value = Box::new(1.0);
 ///     let ref_value = &concrete_value;
-///     let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-///     let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
+///     let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+///     let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
 ///     let lifted = guard.lift();
 ///     tokio::spawn(async move {
 ///         // Lifted is 'static so it can be moved into this closure that needs 'static
@@ -61,6 +74,10 @@ macro_rules! scoped_pin {
 /// }
 /// ```
 ///
+/// Like `&T`, a [`ScopedPinGuard`] and the derived [`ScopedPin`] are `Send` and `Sync` exactly when `T`
+/// is `Sync`. The guard can only be sent to another thread before it is pinned, since it cannot be
+/// moved afterwards.
+///
 /// If a [`ScopedPinGuard`] is dropped while any derived [`ScopedPin`] exist, then it will abort the whole
 /// program (instead of panic). This is because [`ScopedPin`] could exist on another thread and be unaffected
 /// by the panic or the panic could be recovered from. This could lead to undefined behavior.
@@ -68,6 +85,9 @@ macro_rules! scoped_pin {
 /// Unlike [`crate::ScopedGuard`] this pins the guard to the current stack without boxing. Thus it is more
 /// efficient, but it cannot be moved.
 ///
+/// A guard is reusable, e.g. to lift, spawn and join in a loop. Only the count at the time of the
+/// drop matters, so it may go back to zero and up again any number of times.
+///
 /// UNDEFINED BEHAVIOR: It may cause undefined behavior to leak/forget this value. Since
 /// the `Drop` code must run to prevent undefined behavior.
 /// e.g. [`std::mem::forget`], [`std::mem::ManuallyDrop`], or Rc cycles, etc.
@@ -76,133 +96,1836 @@ macro_rules! scoped_pin {
 #[derive(Debug)]
 pub struct ScopedPinGuard<'a, T: 'static> {
     value: &'static T,
-    counter: AtomicUsize,
+    counter: Counter,
     _scope: PhantomData<&'a ()>,
     _unpinnable: PhantomPinned,
 }
 
 impl<'a, T: 'static> ScopedPinGuard<'a, T> {
-    /// Creates a new [`ScopedPinGuard`]. See [`scoped_static`] for a safe way to create.
-    pub unsafe fn new(value: &'a T) -> Self {
-        let value = unsafe { mem::transmute::<&'a T, &'static T>(value) };
-        let counter = AtomicUsize::new(0);
-        ScopedPinGuard {
+    loom_const_fn! {
+        /// Creates a new [`ScopedPinGuard`]. See [`scoped_pin`] for a safe way to create.
+        ///
+        /// This is a `const fn` (except under the `loom` feature), since extending the lifetime is
+        /// only a reinterpretation that needs no runtime code. So is the counter, which starts at
+        /// zero without allocating. What cannot happen in a const context is pinning the guard,
+        /// which needs its final place.
+        ///
+        /// # Safety
+        ///
+        /// The returned guard must be pinned before lifting and must not be leaked/forgotten.
+        /// See [`ScopedPinGuard`].
+        pub unsafe fn new(value: &'a T) -> Self {
+            let value = unsafe { mem::transmute::<&'a T, &'static T>(value) };
+            let counter = Counter::new();
+            ScopedPinGuard {
+                value,
+                counter,
+                _scope: core::marker::PhantomData,
+                _unpinnable: core::marker::PhantomPinned,
+            }
+        }
+    }
+
+    loom_const_fn! {
+        /// Creates a new [`ScopedPinGuardPinned`] over a value that is already pinned, e.g. a field
+        /// of a pinned future. The derived [`ScopedPinPinned`] keep the pinning guarantee, so they
+        /// can hand out `Pin<&T>` for `!Unpin` values like intrusive list nodes. See
+        /// [`crate::scoped_pin_pinned`] for a safe way to create.
+        ///
+        /// # Safety
+        ///
+        /// Same as [`ScopedPinGuard::new`].
+        pub unsafe fn new_from_pin(value: Pin<&'a T>) -> ScopedPinGuardPinned<'a, T> {
+            ScopedPinGuardPinned {
+                guard: unsafe { ScopedPinGuard::new(value.get_ref()) },
+            }
+        }
+    }
+
+    loom_const_fn! {
+        /// Like [`ScopedPinGuard::new`], but also confines the guard to the scope of `token`, e.g. a
+        /// block narrower than the borrow of `value`. Moving the guard, or anything that borrows it,
+        /// out of that scope is then a compile error. See [`ScopeToken`].
+        ///
+        /// ```rust,compile_fail
+        /// use scoped_static::{ScopeToken, ScopedPinGuard};
+        ///
+        /// let value = 1.0;
+        /// let escaped = {
+        ///     let token = ScopeToken::new();
+        ///     unsafe { ScopedPinGuard::new_with(&value, &token) }
+        /// };
+        /// ```
+        ///
+        /// # Safety
+        ///
+        /// Same as [`ScopedPinGuard::new`]. The token does not keep the guard from being leaked.
+        pub unsafe fn new_with(value: &'a T, _token: &'a ScopeToken) -> Self {
+            unsafe { ScopedPinGuard::new(value) }
+        }
+    }
+
+    /// Lifts this reference with lifetime `'a` into `'static` and relies on runtime
+    /// checks to ensure safety.
+    ///
+    /// Only a shared pinned reference is needed, which is `Copy`, so it can lift any number of times
+    /// and be handed to other functions. A `Pin<&mut Self>` can be turned into one with [`Pin::as_ref`].
+    ///
+    /// Without the `lift_backtrace` and `notify` features, this compiles down to a single atomic
+    /// increment and a never taken branch to abort on overflow, and dropping the [`ScopedPin`] to a
+    /// single atomic decrement. `tests/codegen.rs` checks the assembly for regressions.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    #[inline]
+    pub fn lift(self: Pin<&Self>) -> ScopedPin<T> {
+        self.counter.increment();
+        ScopedPin {
+            value: self.value,
+            lift: self.counter.lifts.record(),
+            counter: NonNull::from_ref(&self.counter),
+        }
+    }
+
+    /// Like [`ScopedPinGuard::lift`], but returns `None` if `max` or more [`ScopedPin`] derived from
+    /// this guard already exist. The check and the increment are a single atomic step.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn try_lift(self: Pin<&Self>, max: usize) -> Option<ScopedPin<T>> {
+        if !self.counter.try_increment(max) {
+            return None;
+        }
+        Some(ScopedPin {
+            value: self.value,
+            lift: self.counter.lifts.record(),
+            counter: NonNull::from_ref(&self.counter),
+        })
+    }
+
+    /// The number of [`ScopedPin`] derived from this guard that currently exist. This guard can only
+    /// be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.counter.count.load(Ordering::Acquire)
+    }
+
+    /// The count of the derived [`ScopedPin`], e.g. to inspect it from across an FFI boundary or for
+    /// custom wait logic. The same address is returned by [`ScopedPin::counter_ptr`] for every derived
+    /// [`ScopedPin`]. It is valid for as long as this guard, which is pinned.
+    ///
+    /// Reading through it is always fine. Changing the count is up to the caller, who must uphold:
+    /// - It is never lowered below the number of [`ScopedPin`] that exist, otherwise this guard may
+    ///   end the borrow while some of them are still in use.
+    /// - Every increment is matched by a decrement before this guard is dropped, otherwise it aborts.
+    /// - It does not exceed `isize::MAX`, so it cannot wrap around.
+    ///
+    /// With the `notify` feature, a decrement through the pointer does not wake up threads waiting
+    /// in [`ScopedPinGuard::join`], which then only return on the next drop of a [`ScopedPin`].
+    #[cfg(not(any(feature = "loom", feature = "shuttle")))]
+    pub fn counter_ptr(self: Pin<&Self>) -> *const AtomicUsize {
+        &self.get_ref().counter.count
+    }
+
+    /// Points this guard at `new` if no derived [`ScopedPin`] exist. Otherwise `new` is returned back.
+    /// See [`crate::ScopedGuard::replace`].
+    ///
+    /// This needs a `Pin<&mut Self>`, e.g. from [`core::pin::pin!`], since lifting only needs a
+    /// shared reference. So no lift can happen between the check and the swap.
+    ///
+    /// ```rust
+    /// use std::pin::pin;
+    /// use scoped_static::ScopedPinGuard;
+    ///
+    /// let (first, second) = (1, 2);
+    /// let mut guard = pin!(unsafe { ScopedPinGuard::new(&first) });
+    /// let lifted = guard.as_ref().lift();
+    /// assert_eq!(guard.as_mut().replace(&second), Err(&2));
+    /// drop(lifted);
+    /// assert_eq!(guard.as_mut().replace(&second), Ok(()));
+    /// assert_eq!(*guard.as_ref().lift(), 2);
+    /// ```
+    pub fn replace(self: Pin<&mut Self>, new: &'a T) -> Result<(), &'a T> {
+        if self.lifted_count() != 0 {
+            return Err(new);
+        }
+        // The reference is not structurally pinned, only the counter is
+        let this = unsafe { self.get_unchecked_mut() };
+        this.value = unsafe { mem::transmute::<&'a T, &'static T>(new) };
+        Ok(())
+    }
+
+    /// Drops the guard like the implicit drop, but returns a [`LeakError`] instead of aborting if any
+    /// derived [`ScopedPin`] exist. e.g. for the test harness of a downstream crate to assert that a
+    /// leak is detected, without killing the test process. The implicit drop still aborts.
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedPinGuard;
+    ///
+    /// let value = 1.0;
+    /// let guard_unpinned = unsafe { ScopedPinGuard::new(&value) };
+    /// let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+    /// let lifted = guard.lift();
+    /// let error = unsafe { guard_unpinned.drop_checked() }.unwrap_err();
+    /// assert_eq!(error.lifted_count(), 1);
+    /// std::mem::forget(lifted);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// This moves the guard, while the derived [`ScopedPin`] point to where it was pinned. So it must
+    /// be called on the guard itself once the pinned reference to it is no longer used, and no
+    /// derived [`ScopedPin`] may be dropped concurrently, e.g. join the threads holding them first.
+    /// On `Err`, the remaining [`ScopedPin`] must be leaked, e.g. with [`std::mem::forget`], and
+    /// never be used or dropped.
+    pub unsafe fn drop_checked(self) -> Result<(), LeakError> {
+        let lifted_count = self.counter.count.load(Ordering::Acquire);
+        if lifted_count != 0 {
+            let error = LeakError::new(lifted_count, &self.counter.lifts);
+            mem::forget(self);
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Blocks the current thread until no derived [`ScopedPin`] exist, after which this guard can be
+    /// dropped without aborting. With the `notify` feature the thread is parked until the last
+    /// [`ScopedPin`] is dropped, otherwise it waits with exponential backoff.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join(&self) {
+        self.counter.drained.wait(|| self.lifted_count() == 0, None);
+    }
+
+    /// Like [`ScopedPinGuard::join`], but gives up once `timeout` has elapsed. Returns `true` if no
+    /// derived [`ScopedPin`] exist.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join_timeout(&self, timeout: Duration) -> bool {
+        self.counter
+            .drained
+            .wait(|| self.lifted_count() == 0, Some(Instant::now() + timeout))
+    }
+}
+
+impl<'a, T> Deref for ScopedPinGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<'a, T: 'static> Drop for ScopedPinGuard<'a, T> {
+    fn drop(&mut self) {
+        // Acquire synchronizes with the Release decrement of every dropped `ScopedPin`, so all their
+        // uses of the value happen before the borrow ends.
+        // Once zero, the count cannot go up again while this runs, so there is no window for a late
+        // lift: `lift` borrows the guard, which ends before the drop, and a `ScopedPin` can only be
+        // cloned from one that is still counted.
+        let count = self.counter.count.load(Ordering::Acquire);
+        if count != 0 {
+            utils::abort_with(count, &self.counter.lifts);
+        }
+        self.counter.drained.synchronize();
+    }
+}
+
+/// Marks a scope that guards created with [`ScopedPinGuard::new_with`] cannot leave, since they
+/// borrow the token for their lifetime. e.g. to keep a guard for a long-lived value local to the
+/// block that joins the work it lifts for:
+///
+/// ```rust
+/// use scoped_static::{ScopeToken, ScopedPinGuard};
+///
+/// let value = vec![1, 2, 3];
+/// {
+///     let token = ScopeToken::new();
+///     let guard = std::pin::pin!(unsafe { ScopedPinGuard::new_with(&value, &token) });
+///     let lifted = guard.as_ref().lift();
+///     std::thread::spawn(move || assert_eq!(lifted.len(), 3))
+///         .join()
+///         .unwrap();
+/// }
+/// ```
+///
+/// The borrow of the value already cannot end while a [`ScopedPinGuard`] exists, with or without a
+/// token, so this only narrows where the guard may live. It does not make creating the guard safe,
+/// since leaking it still ends the borrow without checking the count.
+#[derive(Debug, Default)]
+pub struct ScopeToken {
+    _private: (),
+}
+
+impl ScopeToken {
+    /// Creates a token for the current scope.
+    pub const fn new() -> Self {
+        ScopeToken { _private: () }
+    }
+}
+
+/// A safe way to create a [`ScopedPinGuardMulti`].
+/// ```rust
+/// use scoped_static::scoped_pin_multi;
+///
+/// let number = 1u32;
+/// let name = String::from("one");
+/// scoped_pin_multi!(guard);
+/// let lifted_number = guard.lift(&number);
+/// let lifted_name = guard.lift(&name);
+/// std::thread::spawn(move || assert_eq!(format!("{lifted_name} {lifted_number}"), "one 1"))
+///     .join()
+///     .unwrap();
+/// ```
+#[macro_export]
+macro_rules! scoped_pin_multi {
+    ($guard_ident:ident) => {
+        let $guard_ident = unsafe { $crate::ScopedPinGuardMulti::new() };
+        let $guard_ident = unsafe { ::core::pin::Pin::new_unchecked(&$guard_ident) };
+    };
+}
+
+/// Like [`ScopedPinGuard`], but lifts any number of references with lifetime `'a`, of any types,
+/// under one counter. Runtime checks are used to ensure that no derived [`ScopedPin`] of any type
+/// exists when this [`ScopedPinGuardMulti`] is dropped.
+///
+/// The same rules as for [`ScopedPinGuard`] apply. It aborts if dropped while any derived
+/// [`ScopedPin`] exist, and it may cause undefined behavior to leak/forget this value.
+///
+/// See [`scoped_pin_multi`] macro for a safe way to create.
+#[derive(Debug)]
+pub struct ScopedPinGuardMulti<'a> {
+    counter: Counter,
+    // Invariant, so the guard cannot be shortened to lift references that die before it.
+    _scope: PhantomData<&'a mut &'a ()>,
+    _unpinnable: PhantomPinned,
+}
+
+impl<'a> ScopedPinGuardMulti<'a> {
+    loom_const_fn! {
+        /// Creates a new [`ScopedPinGuardMulti`]. See [`scoped_pin_multi`] for a safe way to create.
+        ///
+        /// Since no reference is needed up front, this can be used to create a disarmed guard in a
+        /// const context (except under the `loom` feature), e.g. in a `thread_local!`, which is
+        /// armed at runtime by lifting through it. The lifetime `'a` is still fixed by the type of
+        /// the guard though, which is `'static` for a thread local or `static`. So such a guard can
+        /// only lift references that already live that long, and mainly serves to count and
+        /// [`ScopedPinGuardMulti::lifted_count`] them.
+        ///
+        /// ```rust
+        /// use std::pin::Pin;
+        /// use scoped_static::ScopedPinGuardMulti;
+        ///
+        /// thread_local! {
+        ///     static GUARD: ScopedPinGuardMulti<'static> = const { unsafe { ScopedPinGuardMulti::new() } };
+        /// }
+        ///
+        /// static VALUE: u32 = 1;
+        /// GUARD.with(|guard| {
+        ///     // A thread local is never moved
+        ///     let guard = unsafe { Pin::new_unchecked(guard) };
+        ///     let lifted = guard.lift(&VALUE);
+        ///     assert_eq!(guard.lifted_count(), 1);
+        ///     drop(lifted);
+        /// });
+        /// ```
+        ///
+        /// # Safety
+        ///
+        /// The returned guard must be pinned before lifting and must not be leaked/forgotten.
+        /// See [`ScopedPinGuardMulti`].
+        pub unsafe fn new() -> Self {
+            ScopedPinGuardMulti {
+                counter: Counter::new(),
+                _scope: PhantomData,
+                _unpinnable: PhantomPinned,
+            }
+        }
+    }
+
+    /// Lifts `value` with lifetime `'a` into `'static` and relies on runtime checks to ensure safety.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn lift<T: 'static>(self: Pin<&Self>, value: &'a T) -> ScopedPin<T> {
+        self.counter.increment();
+        ScopedPin {
+            value: unsafe { mem::transmute::<&'a T, &'static T>(value) },
+            lift: self.counter.lifts.record(),
+            counter: NonNull::from_ref(&self.counter),
+        }
+    }
+
+    /// The number of [`ScopedPin`] derived from this guard that currently exist, of all types. This
+    /// guard can only be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.counter.count.load(Ordering::Acquire)
+    }
+}
+
+impl<'a> Drop for ScopedPinGuardMulti<'a> {
+    fn drop(&mut self) {
+        // Acquire for the same reasons as in `ScopedPinGuard::drop`
+        let count = self.counter.count.load(Ordering::Acquire);
+        if count != 0 {
+            utils::abort_with(count, &self.counter.lifts);
+        }
+        self.counter.drained.synchronize();
+    }
+}
+
+/// A safe way to create a [`ScopedPinGuardOwned`].
+/// ```rust
+/// use scoped_static::scoped_pin_owned;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     scoped_pin_owned!(guard, vec![1, 2, 3]);
+///     let lifted = guard.lift();
+///     tokio::spawn(async move {
+///         // Lifted is 'static so it can be moved into this closure that needs 'static
+///         assert_eq!(lifted.iter().sum::<i32>(), 6);
+///     })
+///     .await
+///     .unwrap();
+///    // `guard` and the value it owns are dropped here
+/// }
+/// ```
+#[macro_export]
+macro_rules! scoped_pin_owned {
+    ($guard_ident:ident, $value:expr) => {
+        let $guard_ident = {
+            let value = $value;
+            unsafe { $crate::ScopedPinGuardOwned::new(value) }
+        };
+        let $guard_ident = unsafe { ::core::pin::Pin::new_unchecked(&$guard_ident) };
+    };
+}
+
+/// Like [`ScopedPinGuard`], but owns the value instead of borrowing it. The value is pinned in place
+/// together with the guard, and derived [`ScopedPin`] point into it. The value is only dropped once
+/// the guard is, which requires that no derived [`ScopedPin`] exist.
+///
+/// The same rules as for [`ScopedPinGuard`] apply. It aborts if dropped while any derived
+/// [`ScopedPin`] exist, and it may cause undefined behavior to leak/forget this value.
+///
+/// See [`scoped_pin_owned`] macro for a safe way to create.
+#[derive(Debug)]
+pub struct ScopedPinGuardOwned<T: 'static> {
+    value: T,
+    counter: Counter,
+    _unpinnable: PhantomPinned,
+}
+
+impl<T: 'static> ScopedPinGuardOwned<T> {
+    loom_const_fn! {
+        /// Creates a new [`ScopedPinGuardOwned`]. See [`scoped_pin_owned`] for a safe way to create.
+        ///
+        /// # Safety
+        ///
+        /// The returned guard must be pinned before lifting and must not be leaked/forgotten.
+        /// See [`ScopedPinGuardOwned`].
+        pub unsafe fn new(value: T) -> Self {
+            ScopedPinGuardOwned {
+                value,
+                counter: Counter::new(),
+                _unpinnable: PhantomPinned,
+            }
+        }
+    }
+
+    /// Lifts a reference to the owned value into `'static` and relies on runtime checks to ensure
+    /// safety.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn lift(self: Pin<&Self>) -> ScopedPin<T> {
+        self.counter.increment();
+        let this = self.get_ref();
+        ScopedPin {
+            // The value is pinned together with the guard, which outlives the count
+            value: unsafe { mem::transmute::<&T, &'static T>(&this.value) },
+            lift: this.counter.lifts.record(),
+            counter: NonNull::from_ref(&this.counter),
+        }
+    }
+
+    /// The number of [`ScopedPin`] derived from this guard that currently exist. This guard, and thus
+    /// the owned value, can only be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.counter.count.load(Ordering::Acquire)
+    }
+
+    /// Blocks the current thread until no derived [`ScopedPin`] exist. See [`ScopedPinGuard::join`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join(&self) {
+        self.counter.drained.wait(|| self.lifted_count() == 0, None);
+    }
+}
+
+impl<T> Deref for ScopedPinGuardOwned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T: 'static> Drop for ScopedPinGuardOwned<T> {
+    fn drop(&mut self) {
+        // Acquire for the same reasons as in `ScopedPinGuard::drop`. The owned value is only dropped
+        // after this returns.
+        let count = self.counter.count.load(Ordering::Acquire);
+        if count != 0 {
+            utils::abort_with(count, &self.counter.lifts);
+        }
+        self.counter.drained.synchronize();
+    }
+}
+
+/// A safe way to create a [`ScopedPinGuardPinned`] from a `Pin<&T>`.
+/// ```rust
+/// use std::marker::PhantomPinned;
+/// use std::pin::{pin, Pin};
+/// use scoped_static::{scoped_pin_pinned, ScopedPinPinned};
+///
+/// struct Node {
+///     value: u32,
+///     _pinned: PhantomPinned,
+/// }
+///
+/// impl Node {
+///     fn value(self: Pin<&Self>) -> u32 {
+///         self.value
+///     }
+/// }
+///
+/// let node = pin!(Node { value: 1, _pinned: PhantomPinned });
+/// scoped_pin_pinned!(guard, node.as_ref());
+/// let lifted = guard.lift();
+/// std::thread::spawn(move || assert_eq!(ScopedPinPinned::as_pin(&lifted).value(), 1))
+///     .join()
+///     .unwrap();
+/// ```
+#[macro_export]
+macro_rules! scoped_pin_pinned {
+    ($guard_ident:ident, $pinned_value:expr) => {
+        let $guard_ident = {
+            let pinned_value = $pinned_value;
+            unsafe { $crate::ScopedPinGuard::new_from_pin(pinned_value) }
+        };
+        let $guard_ident = unsafe { ::core::pin::Pin::new_unchecked(&$guard_ident) };
+    };
+}
+
+/// Like [`ScopedPinGuard`], but over a value that is pinned, created with
+/// [`ScopedPinGuard::new_from_pin`]. The derived [`ScopedPinPinned`] can hand out `Pin<&T>`, since
+/// the value is guaranteed to stay in place until it is dropped.
+///
+/// The same rules as for [`ScopedPinGuard`] apply. It aborts if dropped while any derived
+/// [`ScopedPinPinned`] exist, and it may cause undefined behavior to leak/forget this value.
+///
+/// See [`crate::scoped_pin_pinned`] macro for a safe way to create.
+#[derive(Debug)]
+pub struct ScopedPinGuardPinned<'a, T: 'static> {
+    // Structurally pinned
+    guard: ScopedPinGuard<'a, T>,
+}
+
+impl<'a, T: 'static> ScopedPinGuardPinned<'a, T> {
+    fn guard(self: Pin<&Self>) -> Pin<&ScopedPinGuard<'a, T>> {
+        unsafe { self.map_unchecked(|this| &this.guard) }
+    }
+
+    /// Lifts the pinned reference with lifetime `'a` into `'static` and relies on runtime checks to
+    /// ensure safety.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn lift(self: Pin<&Self>) -> ScopedPinPinned<T> {
+        ScopedPinPinned {
+            lifted: self.guard().lift(),
+        }
+    }
+
+    /// The number of [`ScopedPinPinned`] derived from this guard that currently exist. This guard can
+    /// only be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.guard.lifted_count()
+    }
+
+    /// Blocks the current thread until no derived [`ScopedPinPinned`] exist. See
+    /// [`ScopedPinGuard::join`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join(&self) {
+        self.guard.join();
+    }
+
+    /// The pinned underlying value.
+    pub fn as_pin(&self) -> Pin<&T> {
+        // The value was pinned when creating this guard
+        unsafe { Pin::new_unchecked(self.guard.value) }
+    }
+}
+
+impl<'a, T> Deref for ScopedPinGuardPinned<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.guard.value
+    }
+}
+
+/// A safe way to create a [`ScopedArrayGuard`].
+/// ```rust
+/// use scoped_static::scoped_array;
+///
+/// let value = 1.0;
+/// scoped_array!(guard, 2, &value);
+/// let first = guard.try_lift().unwrap();
+/// let second = guard.try_lift().unwrap();
+/// assert!(guard.try_lift().is_none());
+/// ```
+#[macro_export]
+macro_rules! scoped_array {
+    ($guard_ident:ident, $capacity:expr, $ref_value:expr) => {
+        let $guard_ident = {
+            let ref_value = $ref_value;
+            unsafe { $crate::ScopedArrayGuard::<_, { $capacity }>::new(ref_value) }
+        };
+        let $guard_ident = unsafe { ::core::pin::Pin::new_unchecked(&$guard_ident) };
+    };
+}
+
+/// Like [`ScopedPinGuard`], but at most `N` derived [`ScopedPin`] can be lifted at a time, e.g. for
+/// real-time or embedded code that must bound the outstanding handles. [`ScopedArrayGuard::try_lift`]
+/// returns `None` instead of lifting beyond `N`. The count is kept inline without any allocation, so
+/// it also works without `alloc`.
+///
+/// Cloning a [`ScopedPin`] is not refused, since it does not know the capacity of its guard. Lift
+/// again instead, to stay within `N`.
+///
+/// The same rules as for [`ScopedPinGuard`] apply. It aborts if dropped while any derived
+/// [`ScopedPin`] exist, and it may cause undefined behavior to leak/forget this value.
+///
+/// See [`scoped_array`] macro for a safe way to create.
+#[derive(Debug)]
+pub struct ScopedArrayGuard<'a, T: 'static, const N: usize> {
+    guard: ScopedPinGuard<'a, T>,
+}
+
+impl<'a, T: 'static, const N: usize> ScopedArrayGuard<'a, T, N> {
+    loom_const_fn! {
+        /// Creates a new [`ScopedArrayGuard`]. See [`scoped_array`] for a safe way to create.
+        ///
+        /// # Safety
+        ///
+        /// The returned guard must be pinned before lifting and must not be leaked/forgotten.
+        /// See [`ScopedArrayGuard`].
+        pub unsafe fn new(value: &'a T) -> Self {
+            ScopedArrayGuard {
+                guard: unsafe { ScopedPinGuard::new(value) },
+            }
+        }
+    }
+
+    /// Lifts this reference with lifetime `'a` into `'static` like [`ScopedPinGuard::lift`], unless
+    /// `N` derived [`ScopedPin`] already exist. The check and the increment are a single atomic step.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn try_lift(self: Pin<&Self>) -> Option<ScopedPin<T>> {
+        self.guard().try_lift(N)
+    }
+
+    /// The number of [`ScopedPin`] derived from this guard that currently exist. This guard can only
+    /// be dropped without aborting when this is `0`.
+    pub fn lifted_count(&self) -> usize {
+        self.guard.lifted_count()
+    }
+
+    /// The number of [`ScopedPin`] that can currently still be lifted. A [`ScopedPin`] dropped on
+    /// another thread may raise it at any time.
+    pub fn available(&self) -> usize {
+        N.saturating_sub(self.lifted_count())
+    }
+
+    fn guard(self: Pin<&Self>) -> Pin<&ScopedPinGuard<'a, T>> {
+        // SAFETY: The inner guard is pinned structurally, it is never moved out
+        unsafe { self.map_unchecked(|this| &this.guard) }
+    }
+}
+
+impl<'a, T, const N: usize> Deref for ScopedArrayGuard<'a, T, N> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.guard.value
+    }
+}
+
+/// A reference derived from a [`ScopedPinGuard`]. The lifetime of the underlying
+/// value has been lifted to `'static`. See [`ScopedPinGuard`] for more info.
+///
+/// Like [`Scoped`], comparisons and hashing use the underlying value, so a [`ScopedPin`] can be used
+/// as a map key by its value. Use [`ScopedPin::ptr_eq`] to compare which guard it counts towards.
+///
+/// Like `&T`, a [`ScopedPin`] can only be sent to another thread if `T` is `Sync`:
+///
+/// ```rust,compile_fail
+/// use std::cell::Cell;
+/// use scoped_static::scoped_pin;
+///
+/// let value = Cell::new(1);
+/// scoped_pin!(guard, &value);
+/// let lifted = guard.lift();
+/// std::thread::spawn(move || lifted.set(2));
+/// value.set(3);
+/// ```
+pub struct ScopedPin<T: 'static> {
+    pub(crate) value: &'static T,
+    /// Where this was created, printed if the guard is dropped while this still exists.
+    lift: LiftId,
+    counter: NonNull<Counter>,
+}
+
+/// The count of a pinned guard, which the derived [`ScopedPin`] point to.
+#[derive(Debug)]
+struct Counter {
+    count: AtomicUsize,
+    /// Notified when `count` drops to zero.
+    drained: Drained,
+    /// Where the [`ScopedPin`] that exist were created. See the `lift_backtrace` feature.
+    lifts: Lifts,
+}
+
+impl Counter {
+    /// Like `Arc`, the count is not allowed to come close to wrapping around to zero, e.g. when
+    /// forgetting [`ScopedPin`] in a loop.
+    const MAX: usize = isize::MAX as usize;
+
+    loom_const_fn! {
+        fn new() -> Self {
+            Counter {
+                count: AtomicUsize::new(0),
+                drained: Drained::new(),
+                lifts: Lifts::new(),
+            }
+        }
+    }
+
+    /// Counts a new [`ScopedPin`].
+    #[inline]
+    fn increment(&self) {
+        // Relaxed is enough, like `Arc::clone`. The new handle is only sent to other threads through
+        // synchronizing operations, and the guard cannot be dropped concurrently since it is borrowed
+        // or another handle keeps the count above zero.
+        let count = self.count.fetch_add(1, Ordering::Relaxed);
+        if count > Self::MAX {
+            utils::abort_overflow();
+        }
+    }
+
+    /// Counts a new [`ScopedPin`] unless `max` already exist. Returns whether it was counted.
+    fn try_increment(&self, max: usize) -> bool {
+        // Relaxed for the same reasons as in `increment`. Never wraps, since `count < max`.
+        self.count
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                (count < max).then_some(count + 1)
+            })
+            .is_ok()
+    }
+}
+
+// SAFETY: Only shared references to `T` are handed out, like `&T` does. So sending requires `Sync`,
+// or e.g. a `Cell` could be mutated from two threads.
+unsafe impl<T: 'static + Sync> Send for ScopedPin<T> {}
+unsafe impl<T: 'static + Sync> Sync for ScopedPin<T> {}
+
+impl<T: 'static> ScopedPin<T> {
+    /// Makes a new [`ScopedPin`] for a component of the underlying value. The returned [`ScopedPin`]
+    /// takes over the count held by `this`, so the originating [`ScopedPinGuard`] is kept from being
+    /// dropped without counting twice.
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::map(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value.
+    ///
+    /// ```rust
+    /// use scoped_static::{scoped_pin, ScopedPin};
+    ///
+    /// let value = (1, String::from("one"));
+    /// scoped_pin!(guard, &value);
+    /// let lifted: ScopedPin<String> = ScopedPin::map(guard.lift(), |(_, name)| name);
+    /// assert_eq!(*lifted, "one");
+    /// ```
+    pub fn map<U: 'static, F: FnOnce(&T) -> &U>(this: Self, f: F) -> ScopedPin<U> {
+        let value = f(this.value);
+        let (lift, counter) = (this.lift, this.counter);
+        mem::forget(this);
+        ScopedPin {
             value,
+            lift,
             counter,
-            _scope: std::marker::PhantomData,
-            _unpinnable: std::marker::PhantomPinned,
         }
-    }
+    }
+
+    /// Converts into a movable [`Scoped`], e.g. to cross a boundary that expects an owning handle.
+    ///
+    /// The two handle types count differently: a [`ScopedPinGuard`] counts each [`ScopedPin`] in
+    /// its own counter, while a [`Scoped`] shares an `Arc` with its clones. The returned
+    /// [`Scoped`] takes over the single count that `this` held on the [`ScopedPinGuard`], and releases
+    /// it once the returned [`Scoped`] and all of its clones have been dropped. So the
+    /// [`ScopedPinGuard`] still cannot be dropped without aborting until then. The last of them can be
+    /// converted back with [`Scoped::try_into_pin`].
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::into_arc(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value.
+    #[cfg(feature = "alloc")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn into_arc(this: Self) -> Scoped<T> {
+        Scoped::from(this)
+    }
+
+    /// The count of the originating guard, which this [`ScopedPin`] keeps above zero. See
+    /// [`ScopedPinGuard::counter_ptr`] for what has to be upheld when changing it.
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::counter_ptr(...)`, so
+    /// that it does not conflict with a method of the same name on the underlying value.
+    #[cfg(not(any(feature = "loom", feature = "shuttle")))]
+    pub fn counter_ptr(this: &Self) -> *const AtomicUsize {
+        // SAFETY: The guard outlives this handle
+        unsafe { &this.counter.as_ref().count }
+    }
+
+    /// Formats the underlying value, like `ScopedPin(value)`. The [`Debug`](fmt::Debug) of
+    /// [`ScopedPin`] itself only formats the address.
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::debug_value(...)`, so
+    /// that it does not conflict with a method of the same name on the underlying value.
+    pub fn debug_value(this: &Self) -> impl fmt::Debug + '_
+    where
+        T: fmt::Debug,
+    {
+        utils::DebugValue {
+            name: "ScopedPin",
+            value: this.value,
+        }
+    }
+
+    /// Returns `true` if both [`ScopedPin`] count towards the same [`ScopedPinGuard`], regardless of
+    /// the underlying values.
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::ptr_eq(...)`, so that it
+    /// does not conflict with a method of the same name on the underlying value.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.counter == other.counter
+    }
+
+    /// The address of the count, which identifies the originating guard. See [`crate::ByHandle`].
+    pub(crate) fn guard_ptr(this: &Self) -> *const () {
+        this.counter.as_ptr().cast_const().cast()
+    }
+
+    /// The number of [`ScopedPin`] derived from the originating guard that currently exist, including
+    /// this one. Like [`ScopedPinGuard::lifted_count`], e.g. so a long-running task can release its
+    /// handle early once it is the last one the guard waits for.
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::outstanding_count(...)`,
+    /// so that it does not conflict with a method of the same name on the underlying value.
+    pub fn outstanding_count(this: &Self) -> usize {
+        // SAFETY: The guard outlives this handle
+        unsafe { this.counter.as_ref() }
+            .count
+            .load(Ordering::Acquire)
+    }
+
+    /// Dereferences the underlying value, e.g. `&f64` for a `ScopedPin<Box<f64>>` instead of
+    /// `&**this`. Like [`Option::as_deref`].
+    ///
+    /// This is an associated function that needs to be used as `ScopedPin::as_deref(...)`, so that
+    /// it does not conflict with a method of the same name on the underlying value.
+    pub fn as_deref(this: &Self) -> &T::Target
+    where
+        T: Deref,
+    {
+        this.value
+    }
+}
+
+impl<T: 'static> Deref for ScopedPin<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+
+impl<T: 'static> AsRef<T> for ScopedPin<T> {
+    fn as_ref(&self) -> &T {
+        self.value
+    }
+}
+
+impl<T: 'static> Borrow<T> for ScopedPin<T> {
+    fn borrow(&self) -> &T {
+        self.value
+    }
+}
+
+impl<'s, T: 'static> IntoIterator for &'s ScopedPin<T>
+where
+    &'s T: IntoIterator,
+{
+    type Item = <&'s T as IntoIterator>::Item;
+    type IntoIter = <&'s T as IntoIterator>::IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        <&'s T>::into_iter(self.value)
+    }
+}
+
+impl<T: 'static + fmt::Display> fmt::Display for ScopedPin<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.value, f)
+    }
+}
+
+impl<T: 'static> fmt::Pointer for ScopedPin<T> {
+    /// Formats the address of the underlying value, like `&T` does. Not the address of the count.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&self.value, f)
+    }
+}
+
+/// Formats the address of the underlying value, without reading the value. See
+/// [`ScopedPin::debug_value`] to format the value. Unlike for [`Scoped`], the count is not
+/// included, since it lives in the guard like the value does.
+impl<T: 'static> fmt::Debug for ScopedPin<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedPin")
+            .field("ptr", &(self.value as *const T))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static + PartialEq> PartialEq for ScopedPin<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: 'static + PartialEq> PartialEq<T> for ScopedPin<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.value == other
+    }
+}
+
+impl<T: 'static + PartialEq> PartialEq<&T> for ScopedPin<T> {
+    fn eq(&self, other: &&T) -> bool {
+        self.value == *other
+    }
+}
+
+impl<T: 'static + Eq> Eq for ScopedPin<T> {}
+
+impl<T: 'static + PartialOrd> PartialOrd for ScopedPin<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.value.partial_cmp(other.value)
+    }
+}
+
+impl<T: 'static + PartialOrd> PartialOrd<T> for ScopedPin<T> {
+    fn partial_cmp(&self, other: &T) -> Option<cmp::Ordering> {
+        self.value.partial_cmp(other)
+    }
+}
+
+impl<T: 'static + PartialOrd> PartialOrd<&T> for ScopedPin<T> {
+    fn partial_cmp(&self, other: &&T) -> Option<cmp::Ordering> {
+        self.value.partial_cmp(*other)
+    }
+}
+
+impl<T: 'static + Ord> Ord for ScopedPin<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.value.cmp(other.value)
+    }
+}
+
+impl<T: 'static + Hash> Hash for ScopedPin<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<T: 'static> Clone for ScopedPin<T> {
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    fn clone(&self) -> Self {
+        let lift = unsafe {
+            let counter = self.counter.as_ref();
+            counter.increment();
+            counter.lifts.record()
+        };
+        ScopedPin {
+            value: self.value,
+            lift,
+            counter: self.counter,
+        }
+    }
+}
+
+impl<T: 'static> Drop for ScopedPin<T> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let counter = self.counter.as_ref();
+            counter.lifts.remove(self.lift);
+            // Release so all uses of the value through this handle happen before the guard observes
+            // the decrement.
+            #[cfg(not(feature = "notify"))]
+            counter.count.fetch_sub(1, Ordering::Release);
+            // The guard may be freed as soon as it observes zero. So the last decrement happens
+            // under the lock, which the guard takes before it is dropped.
+            #[cfg(feature = "notify")]
+            if counter
+                .count
+                .fetch_update(Ordering::Release, Ordering::Relaxed, |count| {
+                    (count != 1).then(|| count - 1)
+                })
+                .is_err()
+            {
+                counter
+                    .drained
+                    .decrement_last(|| counter.count.fetch_sub(1, Ordering::Release));
+            }
+        }
+    }
+}
+
+/// A pinned reference derived from a [`ScopedPinGuardPinned`]. Like [`ScopedPin`], but the underlying
+/// value is pinned, so [`ScopedPinPinned::as_pin`] gives a `Pin<&T>`, e.g. to call methods taking
+/// `self: Pin<&Self>`. Dereferencing gives a `&T` like `Pin<&T>` does.
+pub struct ScopedPinPinned<T: 'static> {
+    lifted: ScopedPin<T>,
+}
+
+impl<T: 'static> ScopedPinPinned<T> {
+    /// The pinned underlying value.
+    ///
+    /// This is an associated function that needs to be used as `ScopedPinPinned::as_pin(...)`, so
+    /// that it does not conflict with a method of the same name on the underlying value.
+    pub fn as_pin(this: &Self) -> Pin<&T> {
+        // Only derived from a `ScopedPinGuardPinned`, whose value is pinned
+        unsafe { Pin::new_unchecked(this.lifted.value) }
+    }
+
+    /// Returns `true` if both [`ScopedPinPinned`] count towards the same [`ScopedPinGuardPinned`].
+    /// See [`ScopedPin::ptr_eq`].
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ScopedPin::ptr_eq(&this.lifted, &other.lifted)
+    }
+
+    /// Formats the underlying value, like `ScopedPinPinned(value)`. The [`Debug`](fmt::Debug) of
+    /// [`ScopedPinPinned`] itself only formats the address. See [`ScopedPin::debug_value`].
+    ///
+    /// This is an associated function that needs to be used as `ScopedPinPinned::debug_value(...)`,
+    /// so that it does not conflict with a method of the same name on the underlying value.
+    pub fn debug_value(this: &Self) -> impl fmt::Debug + '_
+    where
+        T: fmt::Debug,
+    {
+        utils::DebugValue {
+            name: "ScopedPinPinned",
+            value: this.lifted.value,
+        }
+    }
+}
+
+impl<T: 'static> Deref for ScopedPinPinned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.lifted.value
+    }
+}
+
+impl<T: 'static> Clone for ScopedPinPinned<T> {
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    fn clone(&self) -> Self {
+        ScopedPinPinned {
+            lifted: self.lifted.clone(),
+        }
+    }
+}
+
+/// Formats the address of the underlying value, without reading the value, like [`ScopedPin`]. See
+/// [`ScopedPinPinned::debug_value`] to format the value.
+impl<T: 'static> fmt::Debug for ScopedPinPinned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedPinPinned")
+            .field("ptr", &(self.lifted.value as *const T))
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: 'static> From<ScopedPinPinned<T>> for ScopedPin<T> {
+    /// Gives up the pinning guarantee, keeping the count.
+    fn from(pinned: ScopedPinPinned<T>) -> Self {
+        pinned.lifted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    struct NonCopy(f32);
+
+    impl NonCopy {
+        pub fn new() -> Self {
+            NonCopy(1.0)
+        }
+        pub fn access_value(&self) {
+            assert_eq!(self.0, 1.0, "If these values are not equal it signals UB");
+        }
+    }
+
+    #[cfg(test)]
+    mod normal_tests {
+        use super::super::{
+            Counter, ScopedArrayGuard, ScopedPin, ScopedPinGuard, ScopedPinGuardMulti,
+            ScopedPinGuardOwned, ScopedPinPinned,
+        };
+        use super::NonCopy;
+        use std::borrow::Borrow;
+        use std::time::Duration;
+
+        #[test]
+        #[should_panic]
+        fn dangling() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn valid() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn lifted_count() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            assert_eq!(guard.lifted_count(), 0);
+            let lifted1 = guard.lift();
+            assert_eq!(guard.lifted_count(), 1);
+            let lifted2 = lifted1.clone();
+            assert_eq!(guard.lifted_count(), 2);
+            std::mem::drop(lifted1);
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(lifted2);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn array_guard() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedArrayGuard::<_, 3>::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let mut lifted: Vec<_> = std::iter::from_fn(|| guard.try_lift()).collect();
+            assert_eq!(lifted.len(), 3);
+            assert_eq!(guard.available(), 0);
+            assert!(guard.try_lift().is_none());
+
+            lifted.truncate(1);
+            assert_eq!(guard.available(), 2);
+            lifted.extend(std::iter::from_fn(|| guard.try_lift()));
+            assert_eq!(lifted.len(), 3);
+            assert!(guard.try_lift().is_none());
+            for lifted in &lifted {
+                lifted.access_value();
+            }
+            std::mem::drop(lifted);
+            assert_eq!(guard.lifted_count(), 0);
+
+            let guard_unpinned = unsafe { ScopedArrayGuard::<_, 0>::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            assert!(guard.try_lift().is_none());
+        }
+
+        #[test]
+        fn try_lift() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted1 = guard.try_lift(2).unwrap();
+            let lifted2 = guard.try_lift(2).unwrap();
+            assert!(guard.try_lift(2).is_none());
+            std::mem::drop(lifted1);
+            let lifted3 = guard.try_lift(2).unwrap();
+            std::mem::drop(lifted2);
+            std::mem::drop(lifted3);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn reuse() {
+            let rounds = if cfg!(miri) { 100 } else { 100_000 };
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            for _ in 0..rounds {
+                let lifted = guard.lift();
+                let cloned = lifted.clone();
+                cloned.access_value();
+                std::mem::drop((lifted, cloned));
+                assert_eq!(guard.lifted_count(), 0);
+            }
+            for _ in 0..10 {
+                let lifted = guard.lift();
+                std::thread::spawn(move || lifted.access_value())
+                    .join()
+                    .unwrap();
+                assert_eq!(guard.lifted_count(), 0);
+            }
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn overflow() {
+            use std::sync::atomic::Ordering;
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            // As if `isize::MAX` handles were forgotten
+            let forgotten = Counter::MAX + 1;
+            guard.counter.count.store(forgotten, Ordering::Relaxed);
+            // Aborts, which the `test` feature turns into a panic
+            let result = std::panic::catch_unwind(|| guard.lift());
+            assert!(result.is_err(), "expected an abort instead of wrapping around");
+            assert!(guard.lifted_count() > forgotten);
+            guard.counter.count.store(0, Ordering::Relaxed);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn lift_shared() {
+            fn lift_twice(
+                guard: std::pin::Pin<&ScopedPinGuard<'_, Box<NonCopy>>>,
+            ) -> (ScopedPin<Box<NonCopy>>, ScopedPin<Box<NonCopy>>) {
+                (guard.lift(), guard.lift())
+            }
+
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let (lifted1, lifted2) = lift_twice(guard);
+            let lifted3 = guard.lift();
+            assert_eq!(guard.lifted_count(), 3);
+            lifted1.access_value();
+            lifted2.access_value();
+            lifted3.access_value();
+            std::mem::drop((lifted1, lifted2, lifted3));
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn lift_pin_mut() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
+            let lifted = guard.as_ref().lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn eq_value() {
+            let concrete_value = Box::new(2.0);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!(lifted, 2.0);
+            assert_eq!(lifted, &2.0);
+            assert_ne!(lifted, 1.0);
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn cmp_value() {
+            let sorted = [1.0, 3.0, 5.0, 7.0];
+            let needle = 5.0;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&needle) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert!(lifted > 3.0);
+            assert!(lifted < sorted.last().unwrap());
+            assert_eq!(
+                sorted.binary_search_by(|element| lifted.partial_cmp(element).unwrap().reverse()),
+                Ok(2)
+            );
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn as_deref() {
+            let concrete_value = Box::new(1.0);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let inner: &f64 = ScopedPin::as_deref(&lifted);
+            assert_eq!(*inner, 1.0);
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn ptr_eq() {
+            let concrete_value = Box::new(1.0);
+            let ref_value = &concrete_value;
+            let guard_unpinned1 = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard1 = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned1) };
+            let guard_unpinned2 = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard2 = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned2) };
+            let lifted1 = guard1.lift();
+            let lifted2 = guard1.lift();
+            let lifted3 = guard2.lift();
+            assert!(ScopedPin::ptr_eq(&lifted1, &lifted2));
+            assert!(ScopedPin::ptr_eq(&lifted1, &lifted1.clone()));
+            assert!(!ScopedPin::ptr_eq(&lifted1, &lifted3));
+            std::mem::drop((lifted1, lifted2, lifted3));
+            std::mem::drop(guard_unpinned1);
+            std::mem::drop(guard_unpinned2);
+        }
+
+        #[test]
+        fn new_with() {
+            let concrete_value = Box::new(NonCopy::new());
+            let token = super::super::ScopeToken::new();
+            let guard = std::pin::pin!(unsafe { ScopedPinGuard::new_with(&concrete_value, &token) });
+            let lifted = guard.as_ref().lift();
+            std::thread::spawn(move || lifted.access_value())
+                .join()
+                .unwrap();
+            assert_eq!(guard.lifted_count(), 0);
+        }
+
+        #[test]
+        fn outstanding_count() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!(ScopedPin::outstanding_count(&lifted), 1);
+            let cloned = lifted.clone();
+            let mapped = ScopedPin::map(guard.lift(), |value| &value.0);
+            assert_eq!(ScopedPin::outstanding_count(&lifted), 3);
+            assert_eq!(ScopedPin::outstanding_count(&mapped), guard.lifted_count());
+            std::mem::drop((lifted, mapped));
+            assert_eq!(ScopedPin::outstanding_count(&cloned), 1);
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(cloned);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn multi() {
+            let number = Box::new(1u32);
+            let name = Box::new(String::from("one"));
+            let guard_unpinned = unsafe { ScopedPinGuardMulti::new() };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted_number = guard.lift(&*number);
+            let lifted_name = guard.lift(&*name);
+            assert_eq!(guard.lifted_count(), 2);
+            let lifted_number_clone = lifted_number.clone();
+            std::thread::spawn(move || {
+                assert_eq!(*lifted_number, 1);
+                assert_eq!(*lifted_name, "one");
+            })
+            .join()
+            .unwrap();
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(lifted_number_clone);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        #[should_panic]
+        fn multi_dangling() {
+            let number = Box::new(1u32);
+            let name = Box::new(String::from("one"));
+            let guard_unpinned = unsafe { ScopedPinGuardMulti::new() };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted_number = guard.lift(&*number);
+            let lifted_name = guard.lift(&*name);
+            std::mem::drop(lifted_number);
+            // Keep the count non-zero without a `ScopedPin` outliving the guard
+            std::mem::forget(lifted_name);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn join() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted.access_value();
+            });
+            guard.join();
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn owned() {
+            use std::sync::atomic::{AtomicBool, Ordering};
+            static DROPPED: AtomicBool = AtomicBool::new(false);
+
+            struct Owned(NonCopy);
+
+            impl Drop for Owned {
+                fn drop(&mut self) {
+                    DROPPED.store(true, Ordering::Relaxed);
+                }
+            }
+
+            let guard_unpinned = unsafe { ScopedPinGuardOwned::new(Owned(NonCopy::new())) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted1 = guard.lift();
+            let lifted2 = guard.lift();
+            assert_eq!(guard.lifted_count(), 2);
+            assert!(ScopedPin::ptr_eq(&lifted1, &lifted2));
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                lifted1.0.access_value();
+            });
+            lifted2.0.access_value();
+            std::mem::drop(lifted2);
+            guard.join();
+            assert!(!DROPPED.load(Ordering::Relaxed));
+            std::mem::drop(guard_unpinned);
+            assert!(DROPPED.load(Ordering::Relaxed));
+        }
+
+        #[test]
+        #[should_panic]
+        fn owned_dangling() {
+            let guard_unpinned = unsafe { ScopedPinGuardOwned::new(NonCopy::new()) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            // Keep the count non-zero without a `ScopedPin` outliving the guard
+            std::mem::forget(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn replace() {
+            let first = Box::new(NonCopy::new());
+            let second = Box::new(NonCopy(2.0));
+            let mut guard = std::pin::pin!(unsafe { ScopedPinGuard::new(&first) });
+            let lifted = guard.as_ref().lift();
+            let rejected = guard.as_mut().replace(&second).err().unwrap();
+            assert!(std::ptr::eq(rejected, &second));
+            assert!(std::ptr::eq(&**guard, &first));
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert!(guard.as_mut().replace(&second).is_ok());
+            let lifted = guard.as_ref().lift();
+            assert_eq!(lifted.0, 2.0);
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(lifted);
+        }
+
+        #[test]
+        fn drop_checked() {
+            let concrete_value = Box::new(NonCopy::new());
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert_eq!(unsafe { guard_unpinned.drop_checked() }, Ok(()));
+
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted1 = guard.lift();
+            let lifted2 = lifted1.clone();
+            let error = unsafe { guard_unpinned.drop_checked() }.unwrap_err();
+            assert_eq!(error.lifted_count(), 2);
+            assert!(
+                error
+                    .to_string()
+                    .starts_with("guard dropped while 2 lifted references still exist")
+            );
+            std::mem::forget((lifted1, lifted2));
+        }
 
-    /// Lifts this reference with lifetime `'a` into `'static` and relies on runtime
-    /// checks to ensure safety.
-    pub fn lift(self: &Pin<&mut Self>) -> ScopedPin<T> {
-        self.counter
-            .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-        ScopedPin {
-            value: self.value,
-            counter: NonNull::from_ref(&self.counter),
+        #[cfg(not(any(feature = "loom", feature = "shuttle")))]
+        #[test]
+        fn counter_ptr() {
+            use std::sync::atomic::Ordering;
+            let concrete_value = Box::new(NonCopy::new());
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let counter = guard.counter_ptr();
+            let read = || unsafe { (*counter).load(Ordering::Acquire) };
+            assert_eq!(read(), guard.lifted_count());
+            let lifted1 = guard.lift();
+            let lifted2 = lifted1.clone();
+            assert_eq!(ScopedPin::counter_ptr(&lifted1), counter);
+            assert_eq!(read(), 2);
+            assert_eq!(read(), guard.lifted_count());
+            // Hold an extra count from the outside, like a handle on the other side of an FFI boundary
+            unsafe { (*counter).fetch_add(1, Ordering::Relaxed) };
+            std::mem::drop((lifted1, lifted2));
+            assert_eq!(guard.lifted_count(), 1);
+            unsafe { (*counter).fetch_sub(1, Ordering::Release) };
+            assert_eq!(read(), 0);
+            std::mem::drop(guard_unpinned);
         }
-    }
-}
 
-impl<'a, T> Deref for ScopedPinGuard<'a, T> {
-    type Target = T;
+        #[cfg(not(feature = "loom"))]
+        #[test]
+        fn thread_local_multi() {
+            thread_local! {
+                static GUARD: ScopedPinGuardMulti<'static> =
+                    const { unsafe { ScopedPinGuardMulti::new() } };
+            }
 
-    fn deref(&self) -> &Self::Target {
-        self.value
-    }
-}
+            let value: &'static NonCopy = Box::leak(Box::new(NonCopy::new()));
+            std::thread::spawn(move || {
+                GUARD.with(|guard| {
+                    // A thread local is never moved
+                    let guard = unsafe { std::pin::Pin::new_unchecked(guard) };
+                    assert_eq!(guard.lifted_count(), 0);
+                    let lifted = guard.lift(value);
+                    let handle = std::thread::spawn(move || lifted.access_value());
+                    handle.join().unwrap();
+                    assert_eq!(guard.lifted_count(), 0);
+                    let lifted = guard.lift(value);
+                    assert_eq!(guard.lifted_count(), 1);
+                    std::mem::drop(lifted);
+                });
+                // The guard is dropped with the thread
+            })
+            .join()
+            .unwrap();
+        }
 
-impl<'a, T: 'static> Drop for ScopedPinGuard<'a, T> {
-    fn drop(&mut self) {
-        let count = self.counter.load(std::sync::atomic::Ordering::SeqCst);
-        if count != 0 {
-            utils::abort();
+        #[cfg(not(feature = "loom"))]
+        #[test]
+        fn static_guard() {
+            static VALUE: NonCopy = NonCopy(1.0);
+            static GUARD: ScopedPinGuard<'static, NonCopy> = unsafe { ScopedPinGuard::new(&VALUE) };
+            // A static is never moved or dropped
+            let guard = std::pin::Pin::static_ref(&GUARD);
+            let lifted = guard.lift();
+            assert_eq!(guard.lifted_count(), 1);
+            lifted.access_value();
+            std::mem::drop(lifted);
+            assert_eq!(guard.lifted_count(), 0);
         }
-    }
-}
 
-/// A reference derived from a [`ScopedPinGuard`]. The lifetime of the underlying
-/// value has been lifted to `'static`. See [`ScopedPinGuard`] for more info.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct ScopedPin<T: 'static> {
-    value: &'static T,
-    counter: NonNull<AtomicUsize>,
-}
+        struct Node {
+            inner: NonCopy,
+            _pinned: std::marker::PhantomPinned,
+        }
 
-unsafe impl<T: 'static + Send> Send for ScopedPin<T> {}
-unsafe impl<T: 'static + Sync> Sync for ScopedPin<T> {}
+        impl Node {
+            fn access_value(self: std::pin::Pin<&Self>) {
+                self.inner.access_value();
+            }
+        }
 
-impl<T: 'static> Deref for ScopedPin<T> {
-    type Target = T;
+        #[tokio::test]
+        async fn pinned() {
+            let node = std::pin::pin!(Node {
+                inner: NonCopy::new(),
+                _pinned: std::marker::PhantomPinned,
+            });
+            let node = node.as_ref();
+            let guard_unpinned = unsafe { ScopedPinGuard::new_from_pin(node) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted1 = guard.lift();
+            let lifted2 = lifted1.clone();
+            assert_eq!(guard.lifted_count(), 2);
+            assert!(ScopedPinPinned::ptr_eq(&lifted1, &lifted2));
+            // The address is kept through the lift
+            assert!(std::ptr::eq(&*ScopedPinPinned::as_pin(&lifted1), node.get_ref()));
+            tokio::spawn(async move {
+                ScopedPinPinned::as_pin(&lifted1).access_value();
+                lifted1.inner.access_value();
+            })
+            .await
+            .unwrap();
+            let lifted2: ScopedPin<Node> = lifted2.into();
+            lifted2.inner.access_value();
+            std::mem::drop(lifted2);
+            guard_unpinned.as_pin().access_value();
+            std::mem::drop(guard_unpinned);
+        }
 
-    fn deref(&self) -> &Self::Target {
-        self.value
-    }
-}
+        #[test]
+        #[should_panic]
+        fn pinned_dangling() {
+            let node = std::pin::pin!(Node {
+                inner: NonCopy::new(),
+                _pinned: std::marker::PhantomPinned,
+            });
+            let guard_unpinned = unsafe { ScopedPinGuard::new_from_pin(node.as_ref()) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            // Keep the count non-zero without a `ScopedPinPinned` outliving the guard
+            std::mem::forget(lifted);
+            std::mem::drop(guard_unpinned);
+        }
 
-impl<T: 'static> Clone for ScopedPin<T> {
-    fn clone(&self) -> Self {
-        unsafe {
-            let counter = self.counter.as_ref();
-            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        #[test]
+        fn join_wakes_on_last_drop() {
+            use std::sync::atomic::{AtomicBool, Ordering};
+            static RELEASED: AtomicBool = AtomicBool::new(false);
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted1 = guard.lift();
+            let lifted2 = guard.lift();
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                std::mem::drop(lifted1);
+                std::thread::sleep(Duration::from_millis(50));
+                lifted2.access_value();
+                RELEASED.store(true, Ordering::Relaxed);
+                std::mem::drop(lifted2);
+            });
+            guard.join();
+            // Set before the last drop, so waking up on the first drop would miss it
+            assert!(RELEASED.load(Ordering::Relaxed));
+            std::mem::drop(guard_unpinned);
+            handle.join().unwrap();
         }
-        ScopedPin {
-            value: self.value,
-            counter: self.counter,
+
+        #[test]
+        fn join_timeout() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert!(!guard.join_timeout(Duration::from_millis(10)));
+            let handle = std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                lifted.access_value();
+            });
+            assert!(guard.join_timeout(Duration::from_secs(10)));
+            handle.join().unwrap();
+            std::mem::drop(guard_unpinned);
         }
-    }
-}
 
-impl<T: 'static> Drop for ScopedPin<T> {
-    fn drop(&mut self) {
-        unsafe {
-            let counter = self.counter.as_ref();
-            counter.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+        #[test]
+        fn as_ref_and_borrow() {
+            fn len(value: impl AsRef<String>) -> usize {
+                value.as_ref().len()
+            }
+
+            fn borrowed_len(value: impl Borrow<String>) -> usize {
+                value.borrow().len()
+            }
+
+            let name = Box::new(String::from("one"));
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*name) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            assert_eq!(len(guard.lift()), 3);
+            assert_eq!(borrowed_len(guard.lift()), 3);
+            std::mem::drop(guard_unpinned);
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    struct NonCopy(f32);
+        #[test]
+        fn debug() {
+            /// Fails the test if its `Debug` is ever called.
+            struct Unreadable;
 
-    impl NonCopy {
-        pub fn new() -> Self {
-            NonCopy(1.0)
+            impl std::fmt::Debug for Unreadable {
+                fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    panic!("the value must not be read")
+                }
+            }
+
+            let value = Box::new(Unreadable);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let debug = format!("{lifted:?}");
+            assert_eq!(debug, format!("ScopedPin {{ ptr: {lifted:p}, .. }}"));
+
+            let value = Box::new(1.0);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!(format!("{:?}", ScopedPin::debug_value(&lifted)), "ScopedPin(1.0)");
+
+            let value = Box::pin(Unreadable);
+            let guard_unpinned = unsafe { ScopedPinGuard::new_from_pin(value.as_ref()) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let debug = format!("{lifted:?}");
+            assert_eq!(debug, format!("ScopedPinPinned {{ ptr: {:p}, .. }}", &*lifted));
+
+            let value = Box::pin(1.0);
+            let guard_unpinned = unsafe { ScopedPinGuard::new_from_pin(value.as_ref()) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!(
+                format!("{:?}", ScopedPinPinned::debug_value(&lifted)),
+                "ScopedPinPinned(1.0)"
+            );
         }
-        pub fn access_value(&self) {
-            assert_eq!(self.0, 1.0, "If these values are not equal it signals UB");
+
+        #[test]
+        fn hash_map_key() {
+            use std::hash::BuildHasher;
+
+            let one = Box::new(1u32);
+            let other_one = Box::new(1u32);
+            let guard_unpinned1 = unsafe { ScopedPinGuard::new(&*one) };
+            let guard1 = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned1) };
+            let guard_unpinned2 = unsafe { ScopedPinGuard::new(&*other_one) };
+            let guard2 = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned2) };
+            let hasher = std::hash::RandomState::new();
+            assert_eq!(
+                hasher.hash_one(guard1.lift()),
+                hasher.hash_one(guard2.lift())
+            );
+            let mut map = std::collections::HashMap::new();
+            map.insert(guard1.lift(), "one");
+            assert_eq!(map.get(&1), Some(&"one"));
+            assert_eq!(map.get(&guard2.lift()), Some(&"one"));
+            assert_eq!(map.get(&2), None);
+            std::mem::drop(map);
+            std::mem::drop(guard_unpinned1);
+            std::mem::drop(guard_unpinned2);
         }
-    }
 
-    #[cfg(test)]
-    mod normal_tests {
-        use super::super::ScopedPinGuard;
-        use super::NonCopy;
+        #[test]
+        fn into_iter() {
+            let concrete_value = Box::new(vec![1, 2, 3]);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!((&lifted).into_iter().sum::<i32>(), 6);
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
 
         #[test]
-        #[should_panic]
-        fn dangling() {
+        fn display() {
+            let number = Box::new(1.5f64);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*number) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            assert_eq!(lifted.to_string(), number.to_string());
+            assert_eq!(format!("{lifted:>8.2}"), format!("{:>8.2}", *number));
+            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn pointer() {
+            let number = Box::new(1.5f64);
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&*number) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let cloned = lifted.clone();
+            assert_eq!(format!("{lifted:p}"), format!("{cloned:p}"));
+            assert_eq!(format!("{lifted:p}"), format!("{:p}", &*number));
+            std::mem::drop((lifted, cloned));
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn into_arc() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
-            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
-            let lifted = guard.lift();
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = ScopedPin::into_arc(guard.lift());
+            let lifted_clone = lifted.clone();
+            assert_eq!(guard.lifted_count(), 1);
+            std::thread::spawn(move || {
+                lifted_clone.access_value();
+            })
+            .join()
+            .unwrap();
             lifted.access_value();
+            assert_eq!(guard.lifted_count(), 1);
+            std::mem::drop(lifted);
+            assert_eq!(guard.lifted_count(), 0);
             std::mem::drop(guard_unpinned);
         }
 
         #[test]
-        fn valid() {
+        #[should_panic]
+        fn into_arc_dangling() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
-            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
-            let lifted = guard.lift();
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = ScopedPin::into_arc(guard.lift());
             lifted.access_value();
-            std::mem::drop(lifted);
+            std::mem::drop(guard_unpinned);
+        }
+
+        struct Pair {
+            number: u32,
+            name: String,
+        }
+
+        #[test]
+        fn map() {
+            let concrete_value = Box::new(Pair {
+                number: 1,
+                name: String::from("one"),
+            });
+            let ref_value = &*concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let number: ScopedPin<u32> = ScopedPin::map(lifted.clone(), |pair| &pair.number);
+            let name: ScopedPin<String> = ScopedPin::map(lifted, |pair| &pair.name);
+            assert_eq!(*number, 1);
+            assert_eq!(*name, "one");
+            std::mem::drop(number);
+            std::mem::drop(name);
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        #[should_panic]
+        fn map_dangling() {
+            let concrete_value = Box::new(Pair {
+                number: 1,
+                name: String::from("one"),
+            });
+            let ref_value = &*concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let name: ScopedPin<String> = ScopedPin::map(guard.lift(), |pair| &pair.name);
+            assert_eq!(*name, "one");
             std::mem::drop(guard_unpinned);
         }
 
@@ -211,8 +1934,8 @@ mod tests {
         async fn async_dangling() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
-            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
             let lifted = guard.lift();
             lifted.access_value();
             std::mem::drop(guard_unpinned);
@@ -225,8 +1948,8 @@ mod tests {
         async fn async_valid() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
-            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
             let lifted = guard.lift();
             lifted.access_value();
             tokio::spawn(async move {
@@ -236,9 +1959,42 @@ mod tests {
             .unwrap();
             std::mem::drop(guard_unpinned);
         }
+
+        #[test]
+        // smol uses syscalls Miri does not support
+        #[cfg_attr(miri, ignore)]
+        fn smol_valid() {
+            let concrete_value = Box::new(NonCopy::new());
+            let ref_value = &concrete_value;
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            smol::block_on(smol::spawn(async move {
+                smol::future::yield_now().await;
+                lifted.access_value();
+            }));
+            std::mem::drop(guard_unpinned);
+        }
+
+        #[test]
+        fn send_sync() {
+            // What multithreaded runtimes require of the futures they spawn
+            fn assert_send_sync<T: Send + Sync + 'static>() {}
+            assert_send_sync::<ScopedPin<NonCopy>>();
+            assert_send_sync::<ScopedPinPinned<NonCopy>>();
+            // Sending the guard only sends a reference to the value. See `tests/ui/not_send_sync.rs`
+            // for the types that must not be `Send` or `Sync`.
+            fn assert_send_sync_guard<T: Send + Sync>() {}
+            assert_send_sync_guard::<ScopedPinGuard<'_, NonCopy>>();
+            assert_send_sync_guard::<ScopedPinGuardMulti<'_>>();
+            assert_send_sync_guard::<ScopedPinGuardOwned<NonCopy>>();
+            assert_send_sync_guard::<super::super::ScopedPinGuardPinned<'_, NonCopy>>();
+        }
     }
 
-    #[cfg(test)]
+    // These tests exhibit undefined behavior on purpose, so they are skipped under Miri unless
+    // explicitly requested with `--cfg ub_tests`.
+    #[cfg(any(not(miri), ub_tests))]
     mod ub_tests {
         use super::super::ScopedPinGuard;
         use super::NonCopy;
@@ -247,11 +2003,10 @@ mod tests {
         fn undefined_behavior() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
-            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
             let lifted = guard.lift();
             lifted.access_value();
-            std::mem::forget(guard);
             std::mem::forget(guard_unpinned);
             std::mem::drop(concrete_value);
             let result = std::panic::catch_unwind(|| {
@@ -265,11 +2020,12 @@ mod tests {
         }
 
         #[tokio::test]
+        #[allow(clippy::let_and_return)]
         async fn async_undefined_behavior() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
-            let mut guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
-            let guard = unsafe { std::pin::Pin::new_unchecked(&mut guard_unpinned) };
+            let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
             let lifted = guard.lift();
             lifted.access_value();
             let fut = tokio::spawn(async move {
@@ -279,7 +2035,6 @@ mod tests {
                 });
                 result
             });
-            std::mem::forget(guard);
             std::mem::forget(guard_unpinned);
             std::mem::drop(concrete_value);
             let result = fut.await.unwrap();
@@ -292,8 +2047,8 @@ mod tests {
 
     #[cfg(test)]
     mod macro_tests {
-        #![deny(dropping_references)]
-        #![deny(forgetting_references)]
+        #![deny(dropping_copy_types)]
+        #![deny(forgetting_copy_types)]
         use super::NonCopy;
 
         #[test]
@@ -303,7 +2058,7 @@ mod tests {
             scoped_pin!(guard, ref_value);
             let lifted = guard.lift();
             lifted.access_value();
-            #[allow(dropping_references)]
+            #[allow(dropping_copy_types)]
             std::mem::drop(guard);
         }
 
@@ -315,7 +2070,7 @@ mod tests {
             let lifted = guard.lift();
             lifted.access_value();
             std::mem::drop(lifted);
-            #[allow(dropping_references)]
+            #[allow(dropping_copy_types)]
             std::mem::drop(guard);
         }
 
@@ -329,7 +2084,7 @@ mod tests {
                     scoped_pin!(guard, ref_value);
                     lifted = guard.lift();
                     lifted.access_value();
-                    #[allow(dropping_references)]
+                    #[allow(dropping_copy_types)]
                     std::mem::drop(guard);
                 }
                 lifted
@@ -358,10 +2113,29 @@ mod tests {
             })
             .await
             .unwrap();
-            #[allow(dropping_references)]
+            #[allow(dropping_copy_types)]
             std::mem::drop(guard);
         }
 
+        #[test]
+        fn owned_valid() {
+            scoped_pin_owned!(guard, NonCopy::new());
+            let lifted = guard.lift();
+            lifted.access_value();
+            guard.access_value();
+            std::mem::drop(lifted);
+        }
+
+        #[test]
+        fn pinned_valid() {
+            let value = std::pin::pin!(NonCopy::new());
+            scoped_pin_pinned!(guard, value.as_ref());
+            let lifted = guard.lift();
+            crate::ScopedPinPinned::as_pin(&lifted).access_value();
+            guard.access_value();
+            std::mem::drop(lifted);
+        }
+
         #[test]
         fn undefined_behavior() {
             let concrete_value = Box::new(NonCopy::new());
@@ -369,12 +2143,13 @@ mod tests {
             scoped_pin!(guard, ref_value);
             let lifted = guard.lift();
             lifted.access_value();
-            #[allow(forgetting_references)]
+            #[allow(forgetting_copy_types)]
             std::mem::forget(guard);
             lifted.access_value();
         }
 
         #[tokio::test]
+        #[allow(clippy::let_and_return)]
         async fn async_undefined_behavior() {
             let concrete_value = Box::new(NonCopy::new());
             let ref_value = &concrete_value;
@@ -388,7 +2163,7 @@ mod tests {
                 });
                 result
             });
-            #[allow(forgetting_references)]
+            #[allow(forgetting_copy_types)]
             std::mem::forget(guard);
             // std::mem::drop(concrete_value);
             let result = fut.await.unwrap();
@@ -396,3 +2171,194 @@ mod tests {
         }
     }
 }
+
+#[cfg(all(test, feature = "loom"))]
+mod loom_tests {
+    use super::ScopedPinGuard;
+    use loom::cell::UnsafeCell;
+
+    struct Shared(UnsafeCell<usize>);
+
+    // Accesses are checked by loom
+    unsafe impl Sync for Shared {}
+
+    #[test]
+    fn drop_publishes_writes() {
+        loom::model(|| {
+            let concrete_value = Shared(UnsafeCell::new(0));
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            // Not joined, so the only synchronization is through the count
+            loom::thread::spawn(move || {
+                lifted.0.with_mut(|value| unsafe { *value += 1 });
+            });
+            while guard.lifted_count() != 0 {
+                loom::thread::yield_now();
+            }
+            std::mem::drop(guard_unpinned);
+            let value = concrete_value.0.with(|value| unsafe { *value });
+            assert_eq!(value, 1);
+        });
+    }
+    struct Pair {
+        first: UnsafeCell<usize>,
+        second: UnsafeCell<usize>,
+    }
+
+    // Accesses are checked by loom
+    unsafe impl Sync for Pair {}
+
+    #[test]
+    #[cfg(feature = "notify")]
+    fn join_parks_until_last_drop() {
+        loom::model(|| {
+            let concrete_value = Shared(UnsafeCell::new(0));
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted1 = guard.lift();
+            let lifted2 = guard.lift();
+            loom::thread::spawn(move || {
+                std::mem::drop(lifted1);
+            });
+            loom::thread::spawn(move || {
+                lifted2.0.with_mut(|value| unsafe { *value += 1 });
+            });
+            // A lost wakeup would deadlock here, which loom reports
+            guard.join();
+            std::mem::drop(guard_unpinned);
+            let value = concrete_value.0.with(|value| unsafe { *value });
+            assert_eq!(value, 1);
+        });
+    }
+
+    #[test]
+    fn clone_and_drop_on_different_threads() {
+        loom::model(|| {
+            let concrete_value = Pair {
+                first: UnsafeCell::new(0),
+                second: UnsafeCell::new(0),
+            };
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            loom::thread::spawn(move || {
+                // Cloned on this thread, dropped on another
+                let cloned = lifted.clone();
+                loom::thread::spawn(move || {
+                    cloned.second.with_mut(|value| unsafe { *value += 1 });
+                });
+                lifted.first.with_mut(|value| unsafe { *value += 1 });
+            });
+            // A stale zero would let the reads below race with the writes of the other threads
+            while guard.lifted_count() != 0 {
+                loom::thread::yield_now();
+            }
+            std::mem::drop(guard_unpinned);
+            assert_eq!(concrete_value.first.with(|value| unsafe { *value }), 1);
+            assert_eq!(concrete_value.second.with(|value| unsafe { *value }), 1);
+        });
+    }
+
+    #[test]
+    fn clone_of_last_handle_counted_before_drop() {
+        loom::model(|| {
+            let concrete_value = Shared(UnsafeCell::new(0));
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            loom::thread::spawn(move || {
+                // The only way to mint a handle without the guard. The count must never be seen as
+                // zero in between, or the write below would race with the drop of the guard.
+                let cloned = lifted.clone();
+                std::mem::drop(lifted);
+                cloned.0.with_mut(|value| unsafe { *value += 1 });
+            });
+            while guard.lifted_count() != 0 {
+                loom::thread::yield_now();
+            }
+            std::mem::drop(guard_unpinned);
+            let value = concrete_value.0.with(|value| unsafe { *value });
+            assert_eq!(value, 1);
+        });
+    }
+
+    #[test]
+    fn concurrent_clones() {
+        loom::model(|| {
+            let concrete_value = Shared(UnsafeCell::new(0));
+            let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+            let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+            let lifted = guard.lift();
+            let cloned = lifted.clone();
+            let handle = loom::thread::spawn(move || {
+                let cloned_again = cloned.clone();
+                std::mem::drop(cloned);
+                cloned_again.0.with(|value| unsafe { *value })
+            });
+            let lifted_again = lifted.clone();
+            std::mem::drop(lifted);
+            let observed = lifted_again.0.with(|value| unsafe { *value });
+            std::mem::drop(lifted_again);
+            assert_eq!(handle.join().unwrap(), observed);
+            assert_eq!(guard.lifted_count(), 0);
+            std::mem::drop(guard_unpinned);
+        });
+    }
+}
+
+#[cfg(all(test, feature = "shuttle"))]
+mod shuttle_tests {
+    use super::ScopedPinGuard;
+    use crate::sync::shuttle_check;
+    use shuttle::sync::atomic::{AtomicUsize, Ordering};
+    use shuttle::thread;
+
+    const THREADS: usize = 3;
+
+    #[test]
+    fn lift_clone_drop_across_threads() {
+        shuttle_check(|| {
+            let concrete_value = AtomicUsize::new(0);
+            {
+                // Dropped in place, since the last `ScopedPin` may still be releasing the count
+                let guard = std::pin::pin!(unsafe { ScopedPinGuard::new(&concrete_value) });
+                for _ in 0..THREADS {
+                    let lifted = guard.as_ref().lift();
+                    thread::spawn(move || {
+                        // Cloned and dropped in between the loads of the guard below
+                        let cloned = lifted.clone();
+                        lifted.fetch_add(1, Ordering::Relaxed);
+                        std::mem::drop(lifted);
+                        thread::yield_now();
+                        cloned.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+                // Not joined, so the guard only relies on the count of the threads' handles
+                while guard.lifted_count() != 0 {
+                    thread::yield_now();
+                }
+            }
+            assert_eq!(concrete_value.load(Ordering::Relaxed), 2 * THREADS);
+        });
+    }
+
+    #[test]
+    fn join_waits_for_last_drop() {
+        shuttle_check(|| {
+            let concrete_value = AtomicUsize::new(0);
+            {
+                let guard = std::pin::pin!(unsafe { ScopedPinGuard::new(&concrete_value) });
+                for _ in 0..THREADS {
+                    let lifted = guard.as_ref().lift();
+                    thread::spawn(move || {
+                        lifted.fetch_add(1, Ordering::Relaxed);
+                    });
+                }
+                // Parks with `notify`, so a lost wakeup is reported as a deadlock
+                guard.join();
+            }
+            assert_eq!(concrete_value.load(Ordering::Relaxed), THREADS);
+        });
+    }
+}
@@ -0,0 +1,158 @@
+use crate::ScopedGuard;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A tuple of references with lifetime `'a` that can be lifted together with
+/// [`ScopedGuard::new_tuple`]. Implemented for tuples of 1 to 8 references.
+pub trait RefTuple<'a>: sealed::Sealed {
+    /// The same tuple with every reference lifted to `'static`.
+    type Static: StaticRefTuple;
+
+    #[doc(hidden)]
+    unsafe fn into_static(self) -> Self::Static;
+}
+
+/// A tuple of references lifted to `'static`, held by a [`ScopedTuple`].
+pub trait StaticRefTuple: sealed::Sealed + 'static {
+    /// The same tuple with every reference bound to `'s`.
+    type Ref<'s>;
+
+    #[doc(hidden)]
+    fn get(&self) -> Self::Ref<'_>;
+}
+
+/// The target of a [`ScopedGuard`] created with [`ScopedGuard::new_tuple`]. A single count governs
+/// all the references of the tuple.
+///
+/// The references are only accessible through [`ScopedTuple::get`], which bounds them to the borrow
+/// of this value. Otherwise a `'static` reference could be copied out of the tuple and outlive the
+/// guard.
+///
+/// ```rust,compile_fail
+/// use scoped_static::ScopedGuard;
+///
+/// let number = 1;
+/// let guard = unsafe { ScopedGuard::new_tuple((&number,)) };
+/// let lifted = guard.lift();
+/// let escaped: &'static i32 = lifted.get().0;
+/// ```
+pub struct ScopedTuple<R: StaticRefTuple>(R);
+
+impl<R: StaticRefTuple> ScopedTuple<R> {
+    /// The references of the tuple.
+    pub fn get(&self) -> R::Ref<'_> {
+        self.0.get()
+    }
+}
+
+impl<'a, R: StaticRefTuple> ScopedGuard<'a, ScopedTuple<R>> {
+    /// Creates a new [`ScopedGuard`] over a tuple of references with the same lifetime `'a`, but possibly
+    /// different types. See [`ScopedTuple`].
+    ///
+    /// ```rust
+    /// use scoped_static::ScopedGuard;
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let number = Box::new(1.0);
+    ///     let name = String::from("one");
+    ///     let guard = unsafe { ScopedGuard::new_tuple((&number, &name)) };
+    ///     let lifted = guard.lift();
+    ///     tokio::spawn(async move {
+    ///         let (number, name) = lifted.get();
+    ///         assert_eq!(**number, 1.0);
+    ///         assert_eq!(name, "one");
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// }
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// Same as [`ScopedGuard::new`].
+    pub unsafe fn new_tuple<T: RefTuple<'a, Static = R>>(refs: T) -> Self {
+        unsafe { ScopedGuard::new_stored(ScopedTuple(refs.into_static())) }
+    }
+}
+
+macro_rules! impl_ref_tuple {
+    ($($name:ident),+) => {
+        impl<'a, $($name: ?Sized + 'static),+> sealed::Sealed for ($(&'a $name,)+) {}
+
+        impl<'a, $($name: ?Sized + 'static),+> RefTuple<'a> for ($(&'a $name,)+) {
+            type Static = ($(&'static $name,)+);
+
+            #[allow(non_snake_case)]
+            unsafe fn into_static(self) -> Self::Static {
+                let ($($name,)+) = self;
+                ($(unsafe { &*($name as *const $name) },)+)
+            }
+        }
+
+        impl<$($name: ?Sized + 'static),+> StaticRefTuple for ($(&'static $name,)+) {
+            type Ref<'s> = ($(&'s $name,)+);
+
+            #[allow(non_snake_case)]
+            fn get(&self) -> Self::Ref<'_> {
+                let ($($name,)+) = *self;
+                ($($name,)+)
+            }
+        }
+    };
+}
+
+impl_ref_tuple!(A);
+impl_ref_tuple!(A, B);
+impl_ref_tuple!(A, B, C);
+impl_ref_tuple!(A, B, C, D);
+impl_ref_tuple!(A, B, C, D, E);
+impl_ref_tuple!(A, B, C, D, E, F);
+impl_ref_tuple!(A, B, C, D, E, F, G);
+impl_ref_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use crate::ScopedGuard;
+
+    #[test]
+    fn tuple() {
+        let number = Box::new(1.0);
+        let name = String::from("one");
+        let guard = unsafe { ScopedGuard::new_tuple((&*number, &name)) };
+        let (guard_number, guard_name) = guard.get();
+        assert_eq!(*guard_number, 1.0);
+        assert_eq!(guard_name, "one");
+        let lifted = guard.lift();
+        std::thread::spawn(move || {
+            let (number, name) = lifted.get();
+            assert_eq!(*number, 1.0);
+            assert_eq!(name, "one");
+        })
+        .join()
+        .unwrap();
+        assert_eq!(guard.lifted_count(), 0);
+        std::mem::drop(guard);
+    }
+
+    #[test]
+    fn tuple_dangling() {
+        let number = Box::new(1.0);
+        let name = String::from("one");
+        let guard = unsafe { ScopedGuard::new_tuple((&*number, &name)) };
+        let lifted = guard.lift();
+        let lifted_clone = lifted.clone();
+        assert_eq!(guard.lifted_count(), 2);
+        std::mem::drop(lifted);
+        let result = std::panic::catch_unwind(|| {
+            std::mem::drop(guard);
+        });
+        assert!(
+            result.is_err(),
+            "expected panic when dropping ScopeGuard with an alive Scoped of the tuple"
+        );
+        std::mem::drop(lifted_clone);
+    }
+}
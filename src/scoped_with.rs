@@ -0,0 +1,242 @@
+use core::fmt;
+
+use crate::{Scoped, ScopedGuard};
+
+/// A family of types that borrow with a lifetime, e.g. `Foo<'b>`, so that a [`ScopedWithGuard`] can
+/// lift a `&Foo<'b>` without claiming that the borrows inside it are `'static`. It is implemented on a
+/// marker type, since the borrowing type cannot be named without a lifetime.
+///
+/// ```rust
+/// use scoped_static::Family;
+///
+/// struct Foo<'b> {
+///     name: &'b str,
+/// }
+///
+/// struct FooFamily;
+///
+/// impl Family for FooFamily {
+///     type Of<'b> = Foo<'b>;
+/// }
+/// ```
+pub trait Family: 'static {
+    /// The type borrowing with the lifetime `'b`.
+    type Of<'b>: 'b;
+}
+
+/// Like [`ScopedGuard`], but for a value whose type borrows itself, e.g. a `&'a Foo<'b>`. The derived
+/// [`ScopedWith`] are `'static`, but only give access to the value in the closure of
+/// [`ScopedWith::with`], which cannot let the value, or anything it borrows, escape.
+///
+/// ```rust
+/// use scoped_static::{Family, ScopedWithGuard};
+///
+/// struct Foo<'b> {
+///     name: &'b str,
+/// }
+///
+/// struct FooFamily;
+///
+/// impl Family for FooFamily {
+///     type Of<'b> = Foo<'b>;
+/// }
+///
+/// let name = String::from("one");
+/// let foo = Foo { name: &name };
+/// let guard = unsafe { ScopedWithGuard::<FooFamily>::new(&foo) };
+/// let lifted = guard.lift();
+/// let len = std::thread::spawn(move || lifted.with(|foo| foo.name.len()))
+///     .join()
+///     .unwrap();
+/// assert_eq!(len, 3);
+/// ```
+///
+/// The same rules as for [`ScopedGuard`] apply. It aborts if dropped while any derived
+/// [`ScopedWith`] exist, and may cause undefined behavior if leaked/forgotten.
+pub struct ScopedWithGuard<'a, F: Family> {
+    // The borrows of the value are not actually `'static`, so it is only handed out through `with`
+    guard: ScopedGuard<'a, F::Of<'static>>,
+}
+
+impl<'a, F: Family> ScopedWithGuard<'a, F> {
+    /// Creates a new [`ScopedWithGuard`].
+    ///
+    /// # Safety
+    ///
+    /// The returned guard must not be leaked/forgotten. See [`ScopedGuard`].
+    pub unsafe fn new<'b: 'a>(value: &'a F::Of<'b>) -> Self {
+        // SAFETY: Only the lifetime changes. `'b` outlives the guard, which outlives every
+        // `ScopedWith`, and those only hand out the value with a lifetime shorter than their own.
+        let value = unsafe { &*(value as *const F::Of<'b>).cast::<F::Of<'static>>() };
+        ScopedWithGuard {
+            guard: unsafe { ScopedGuard::new(value) },
+        }
+    }
+
+    /// Lifts the reference into a `'static` [`ScopedWith`] and relies on runtime checks to ensure
+    /// safety.
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    pub fn lift(&self) -> ScopedWith<F> {
+        ScopedWith {
+            lifted: self.guard.lift(),
+        }
+    }
+
+    /// The number of [`ScopedWith`] derived from this guard that currently exist.
+    pub fn lifted_count(&self) -> usize {
+        self.guard.lifted_count()
+    }
+
+    /// Calls `f` with the value. See [`ScopedWith::with`].
+    pub fn with<R>(&self, f: impl for<'x> FnOnce(&'x F::Of<'x>) -> R) -> R {
+        f(unsafe { shorten::<F>(&self.guard) })
+    }
+
+    /// Blocks the current thread until no derived [`ScopedWith`] exist, then drops this guard. See
+    /// [`ScopedGuard::join`].
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    pub fn join(self) {
+        self.guard.join();
+    }
+}
+
+impl<'a, F: Family> fmt::Debug for ScopedWithGuard<'a, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedWithGuard")
+            .field("lifted_count", &self.lifted_count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// A `'static` handle to a value whose type borrows, derived from a [`ScopedWithGuard`]. Unlike
+/// [`Scoped`] it does not dereference, since the value would claim to borrow for `'static`. The
+/// value can only be used in [`ScopedWith::with`], where it borrows for the length of the call.
+///
+/// Neither the value nor anything it borrows can escape the closure:
+///
+/// ```rust,compile_fail
+/// use scoped_static::{Family, ScopedWithGuard};
+///
+/// struct Foo<'b> {
+///     name: &'b str,
+/// }
+///
+/// struct FooFamily;
+///
+/// impl Family for FooFamily {
+///     type Of<'b> = Foo<'b>;
+/// }
+///
+/// let name = String::from("one");
+/// let foo = Foo { name: &name };
+/// let guard = unsafe { ScopedWithGuard::<FooFamily>::new(&foo) };
+/// let lifted = guard.lift();
+/// let escaped: &'static str = lifted.with(|foo| foo.name);
+/// ```
+pub struct ScopedWith<F: Family> {
+    lifted: Scoped<F::Of<'static>>,
+}
+
+impl<F: Family> ScopedWith<F> {
+    /// Calls `f` with the value, borrowing for no longer than the call.
+    pub fn with<R>(&self, f: impl for<'x> FnOnce(&'x F::Of<'x>) -> R) -> R {
+        f(unsafe { shorten::<F>(&self.lifted) })
+    }
+}
+
+impl<F: Family> Clone for ScopedWith<F> {
+    #[cfg_attr(feature = "lift_backtrace", track_caller)]
+    fn clone(&self) -> Self {
+        ScopedWith {
+            lifted: self.lifted.clone(),
+        }
+    }
+}
+
+impl<F: Family> fmt::Debug for ScopedWith<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScopedWith").finish_non_exhaustive()
+    }
+}
+
+/// Shortens the borrows of the value to the borrow of it.
+///
+/// # Safety
+///
+/// The value must have been created by [`ScopedWithGuard::new`], whose borrows outlive the guard.
+/// The result must only be passed to a closure that accepts any lifetime, so it cannot store the
+/// value or its borrows anywhere that outlives the call.
+unsafe fn shorten<'x, F: Family>(value: &'x F::Of<'static>) -> &'x F::Of<'x> {
+    unsafe { &*(value as *const F::Of<'static>).cast::<F::Of<'x>>() }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::{Family, ScopedWithGuard};
+
+    struct Foo<'b> {
+        name: &'b str,
+        values: &'b [u32],
+    }
+
+    struct FooFamily;
+
+    impl Family for FooFamily {
+        type Of<'b> = Foo<'b>;
+    }
+
+    #[test]
+    fn lift_borrowing() {
+        let name = String::from("one");
+        let values = vec![1, 2, 3];
+        let foo = Foo {
+            name: &name,
+            values: &values,
+        };
+        let guard = unsafe { ScopedWithGuard::<FooFamily>::new(&foo) };
+        let lifted = guard.lift();
+        let cloned = lifted.clone();
+        assert_eq!(guard.lifted_count(), 2);
+        let result = std::thread::spawn(move || {
+            lifted.with(|foo| format!("{} {}", foo.name, foo.values.iter().sum::<u32>()))
+        })
+        .join()
+        .unwrap();
+        assert_eq!(result, "one 6");
+        assert_eq!(cloned.with(|foo| foo.values.len()), 3);
+        assert_eq!(guard.with(|foo| foo.name.len()), 3);
+        std::mem::drop(cloned);
+        assert_eq!(guard.lifted_count(), 0);
+        guard.join();
+    }
+
+    struct Invariant<'b> {
+        current: Cell<&'b str>,
+        other: &'b str,
+    }
+
+    struct InvariantFamily;
+
+    impl Family for InvariantFamily {
+        type Of<'b> = Invariant<'b>;
+    }
+
+    #[test]
+    fn lift_invariant() {
+        let (one, two) = (String::from("one"), String::from("two"));
+        let value = Invariant {
+            current: Cell::new(&one),
+            other: &two,
+        };
+        let guard = unsafe { ScopedWithGuard::<InvariantFamily>::new(&value) };
+        let lifted = guard.lift();
+        // Only what the value already borrows can be stored back into it
+        lifted.with(|value| value.current.set(value.other));
+        std::mem::drop(lifted);
+        std::mem::drop(guard);
+        assert_eq!(value.current.get(), "two");
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(feature = "alloc")]
+use crate::Scoped;
+use crate::ScopedPin;
+
+/// Implemented by the lifted references that can be moved into a spawned thread or task, i.e. that
+/// are `Send + 'static`. Like `&T`, a lifted reference is `Send` exactly when `T` is `Sync`, since it
+/// only gives shared access to the value. It does not need `T: Send`, the value itself is never
+/// moved to the other thread.
+///
+/// Check a handle with [`assert_spawn_safe`] before capturing it, which points at the handle and the
+/// missing `Sync` instead of at the whole closure passed to the spawn function.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` is not a lifted reference that can be moved to another thread",
+    note = "`SpawnSafe` is implemented for `Scoped<T>` and `ScopedPin<T>` when `T: Sync`"
+)]
+pub trait SpawnSafe: Send + 'static + private::Sealed {}
+
+#[cfg(feature = "alloc")]
+impl<T: 'static + Sync + ?Sized> SpawnSafe for Scoped<T> {}
+impl<T: 'static + Sync> SpawnSafe for ScopedPin<T> {}
+
+/// Fails to compile unless `handle` can be moved to another thread. See [`SpawnSafe`].
+///
+/// ```rust
+/// use scoped_static::{assert_spawn_safe, scoped};
+///
+/// let value = 1.0;
+/// let guard = scoped!(&value);
+/// let lifted = guard.lift();
+/// assert_spawn_safe(&lifted);
+/// std::thread::spawn(move || assert_eq!(*lifted, 1.0)).join().unwrap();
+/// ```
+///
+/// A `Cell` is not `Sync`, so a lifted reference to it cannot be sent:
+///
+/// ```rust,compile_fail
+/// use std::cell::Cell;
+/// use scoped_static::{assert_spawn_safe, scoped};
+///
+/// let value = Cell::new(1);
+/// let guard = scoped!(&value);
+/// assert_spawn_safe(&guard.lift());
+/// ```
+#[inline(always)]
+pub const fn assert_spawn_safe<H: SpawnSafe>(_handle: &H) {}
+
+mod private {
+    #[cfg(feature = "alloc")]
+    use crate::Scoped;
+    use crate::ScopedPin;
+
+    pub trait Sealed {}
+
+    #[cfg(feature = "alloc")]
+    impl<T: 'static + ?Sized> Sealed for Scoped<T> {}
+    impl<T: 'static> Sealed for ScopedPin<T> {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ScopedGuard, ScopedPinGuard};
+
+    #[test]
+    fn spawn() {
+        let value = Box::new(String::from("value"));
+        let guard = unsafe { ScopedGuard::new(&*value) };
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&*value) };
+        let pin_guard = unsafe { core::pin::Pin::new_unchecked(&guard_unpinned) };
+        let lifted = guard.lift();
+        let lifted_pin = pin_guard.lift();
+        assert_spawn_safe(&lifted);
+        assert_spawn_safe(&lifted_pin);
+        std::thread::spawn(move || assert_eq!(*lifted, *lifted_pin))
+            .join()
+            .unwrap();
+        std::mem::drop(guard_unpinned);
+        std::mem::drop(guard);
+    }
+}
@@ -0,0 +1,320 @@
+//! Synchronization primitives, swapped for the [loom](https://docs.rs/loom) ones under the `loom`
+//! feature so the counting can be model checked, or for the [shuttle](https://docs.rs/shuttle) ones
+//! under the `shuttle` feature so it can be stress tested with random schedules. Otherwise the lock
+//! and condvar of the `notify` feature are the [parking_lot](https://docs.rs/parking_lot) ones under
+//! the `parking_lot` feature, or the std ones.
+
+#[cfg(all(feature = "loom", feature = "shuttle"))]
+compile_error!("the `loom` and `shuttle` features cannot be enabled together");
+
+#[cfg(not(any(feature = "loom", feature = "shuttle")))]
+pub(crate) use core::sync::atomic::AtomicUsize;
+#[cfg(feature = "loom")]
+pub(crate) use loom::sync::atomic::AtomicUsize;
+#[cfg(feature = "shuttle")]
+pub(crate) use shuttle::sync::atomic::AtomicUsize;
+#[cfg(all(feature = "notify", feature = "loom"))]
+use loom::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(all(feature = "notify", feature = "shuttle"))]
+use shuttle::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(all(
+    feature = "notify",
+    feature = "parking_lot",
+    not(any(feature = "loom", feature = "shuttle"))
+))]
+use parking_lot::{Condvar, Mutex, MutexGuard};
+#[cfg(feature = "notify")]
+use std::sync::Arc;
+#[cfg(all(
+    feature = "notify",
+    not(all(
+        feature = "parking_lot",
+        not(any(feature = "loom", feature = "shuttle"))
+    ))
+))]
+use std::sync::PoisonError;
+#[cfg(all(
+    feature = "notify",
+    not(any(feature = "loom", feature = "shuttle", feature = "parking_lot"))
+))]
+use std::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(feature = "notify")]
+use std::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Defines a `const fn`, except under the `loom` feature, whose primitives cannot be created in a
+/// const context.
+macro_rules! loom_const_fn {
+    ($(#[$attr:meta])* $vis:vis unsafe fn $($item:tt)*) => {
+        #[cfg(not(feature = "loom"))]
+        $(#[$attr])*
+        $vis const unsafe fn $($item)*
+
+        #[cfg(feature = "loom")]
+        $(#[$attr])*
+        $vis unsafe fn $($item)*
+    };
+    ($(#[$attr:meta])* $vis:vis fn $($item:tt)*) => {
+        #[cfg(not(feature = "loom"))]
+        $(#[$attr])*
+        $vis const fn $($item)*
+
+        #[cfg(feature = "loom")]
+        $(#[$attr])*
+        $vis fn $($item)*
+    };
+}
+pub(crate) use loom_const_fn;
+
+/// Lets threads wait for a count of lifted references to drop to zero. With the `notify` feature
+/// the waiting threads are parked until the last decrement calls [`Drained::notify`], unless turned
+/// off for a guard. Without it this is empty and waiting falls back to exponential backoff.
+#[derive(Debug)]
+pub(crate) struct Drained {
+    #[cfg(feature = "notify")]
+    notify: Notify,
+}
+
+#[cfg(feature = "notify")]
+#[derive(Debug)]
+// The shuttle lock and condvar track their waiters inline, but are only used in tests
+#[cfg_attr(feature = "shuttle", allow(clippy::large_enum_variant))]
+enum Notify {
+    /// A lock and condvar of its own, which needs no allocation.
+    Own(State),
+    /// Shared with other guards. See [`Notifier`].
+    Shared(Notifier),
+    /// Waiting falls back to exponential backoff, like without the `notify` feature.
+    Off,
+}
+
+#[cfg(feature = "notify")]
+#[derive(Debug, Default)]
+struct State {
+    lock: Mutex<()>,
+    condvar: Condvar,
+}
+
+// Like the std lock and condvar that the shuttle and parking_lot ones stand in for, so the guards stay
+// unwind safe. The lock guards no data, so a panic while it is held leaves nothing inconsistent.
+#[cfg(all(feature = "notify", any(feature = "shuttle", feature = "parking_lot")))]
+impl std::panic::RefUnwindSafe for State {}
+#[cfg(all(feature = "notify", any(feature = "shuttle", feature = "parking_lot")))]
+impl std::panic::UnwindSafe for State {}
+
+/// The lock and condition variable that threads waiting for lifted references to be dropped park
+/// on. By default every guard has its own. A [`Notifier`] can instead be shared by a pool of guards
+/// with [`crate::ScopedGuardBuilder::notifier`], e.g. to set it up once for many short-lived guards.
+/// Dropping the last lifted reference of any of them wakes up the threads waiting on all of them,
+/// which then check their own guard again.
+///
+/// Cloning is cheap and shares the same lock and condition variable.
+#[cfg(feature = "notify")]
+#[cfg_attr(docsrs, doc(cfg(feature = "notify")))]
+#[derive(Debug, Clone, Default)]
+pub struct Notifier(Arc<State>);
+
+#[cfg(feature = "notify")]
+impl Notifier {
+    /// Creates a new [`Notifier`], which is not shared with any guard yet.
+    pub fn new() -> Self {
+        Notifier::default()
+    }
+}
+
+#[cfg(all(
+    feature = "notify",
+    not(all(
+        feature = "parking_lot",
+        not(any(feature = "loom", feature = "shuttle"))
+    ))
+))]
+impl State {
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn park<'a>(&self, lock: MutexGuard<'a, ()>) -> MutexGuard<'a, ()> {
+        self.condvar
+            .wait(lock)
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn park_timeout<'a>(&self, lock: MutexGuard<'a, ()>, timeout: Duration) -> MutexGuard<'a, ()> {
+        self.condvar
+            .wait_timeout(lock, timeout)
+            .unwrap_or_else(PoisonError::into_inner)
+            .0
+    }
+}
+
+// The parking_lot lock is not poisoned by a panic, so there is nothing to recover from
+#[cfg(all(
+    feature = "notify",
+    feature = "parking_lot",
+    not(any(feature = "loom", feature = "shuttle"))
+))]
+impl State {
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock()
+    }
+
+    fn park<'a>(&self, mut lock: MutexGuard<'a, ()>) -> MutexGuard<'a, ()> {
+        self.condvar.wait(&mut lock);
+        lock
+    }
+
+    fn park_timeout<'a>(
+        &self,
+        mut lock: MutexGuard<'a, ()>,
+        timeout: Duration,
+    ) -> MutexGuard<'a, ()> {
+        self.condvar.wait_for(&mut lock, timeout);
+        lock
+    }
+}
+
+#[cfg(feature = "notify")]
+impl State {
+    fn wait(&self, is_drained: impl Fn() -> bool, deadline: Option<Instant>) -> bool {
+        // The count is decremented before `notify` takes the lock. So either the check below sees
+        // zero, or the notification happens after this thread started waiting.
+        let mut lock = self.lock();
+        while !is_drained() {
+            match deadline {
+                None => lock = self.park(lock),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return false;
+                    }
+                    lock = self.park_timeout(lock, deadline - now);
+                }
+            }
+        }
+        true
+    }
+}
+
+impl Default for Drained {
+    fn default() -> Self {
+        Drained::new()
+    }
+}
+
+impl Drained {
+    loom_const_fn! {
+        /// Parks the waiting threads on a lock and condvar of its own, if the `notify` feature is
+        /// enabled.
+        pub(crate) fn new() -> Self {
+            Drained {
+                #[cfg(feature = "notify")]
+                notify: Notify::Own(State {
+                    lock: Mutex::new(()),
+                    condvar: Condvar::new(),
+                }),
+            }
+        }
+    }
+
+    /// Parks the waiting threads on `notifier`, which may be shared with other guards.
+    #[cfg(feature = "notify")]
+    pub(crate) fn shared(notifier: Notifier) -> Self {
+        Drained {
+            notify: Notify::Shared(notifier),
+        }
+    }
+
+    /// Waits with exponential backoff instead of parking the waiting threads.
+    #[cfg(feature = "notify")]
+    pub(crate) fn off() -> Self {
+        Drained { notify: Notify::Off }
+    }
+
+    #[cfg(feature = "notify")]
+    fn state(&self) -> Option<&State> {
+        match &self.notify {
+            Notify::Own(state) => Some(state),
+            Notify::Shared(notifier) => Some(&notifier.0),
+            Notify::Off => None,
+        }
+    }
+
+    /// Blocks until `is_drained` returns `true` or the `deadline` has passed. Returns whether it is
+    /// drained.
+    #[cfg(feature = "std")]
+    pub(crate) fn wait(&self, is_drained: impl Fn() -> bool, deadline: Option<Instant>) -> bool {
+        if is_drained() {
+            return true;
+        }
+        #[cfg(feature = "notify")]
+        if let Some(state) = self.state() {
+            return state.wait(is_drained, deadline);
+        }
+        let mut backoff = crate::utils::Backoff::new();
+        while !is_drained() {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return false;
+            }
+            backoff.snooze();
+        }
+        true
+    }
+
+    /// Wakes up the waiting threads. Called after the count dropped to zero.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn notify(&self) {
+        #[cfg(feature = "notify")]
+        if let Some(state) = self.state() {
+            let _lock = state.lock();
+            state.condvar.notify_all();
+        }
+    }
+
+    /// Runs `decrement`, which returns the previous count, under the lock and wakes up the waiting
+    /// threads if the count dropped to zero. For counts that may be freed as soon as they are zero,
+    /// together with [`Drained::synchronize`] before freeing.
+    #[cfg(feature = "notify")]
+    pub(crate) fn decrement_last(&self, decrement: impl FnOnce() -> usize) {
+        match self.state() {
+            Some(state) => {
+                let _lock = state.lock();
+                if decrement() == 1 {
+                    state.condvar.notify_all();
+                }
+            }
+            None => {
+                decrement();
+            }
+        }
+    }
+
+    /// Waits for a concurrent [`Drained::decrement_last`] to release the lock, so this can be freed.
+    #[inline]
+    pub(crate) fn synchronize(&self) {
+        #[cfg(feature = "notify")]
+        if let Some(state) = self.state() {
+            drop(state.lock());
+        }
+    }
+}
+
+/// Runs `f` under the random scheduler of shuttle, for the `shuttle_tests`. The seed is fixed so runs
+/// are reproducible, and can be changed with `SHUTTLE_SEED` to explore other schedules. A failure
+/// prints the seed and the failing schedule, which replays it with `shuttle::replay`.
+#[cfg(all(test, feature = "shuttle"))]
+pub(crate) fn shuttle_check(f: impl Fn() + Send + Sync + 'static) {
+    fn env(name: &str, default: u64) -> u64 {
+        std::env::var(name).map_or(default, |value| {
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("`{name}` must be a number"))
+        })
+    }
+    let seed = env("SHUTTLE_SEED", 0x5c0bed);
+    let iterations = env("SHUTTLE_ITERATIONS", 1_000) as usize;
+    eprintln!("shuttle seed: {seed}");
+    shuttle::check_random_with_seed(f, seed, iterations);
+}
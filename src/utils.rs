@@ -1,28 +1,494 @@
-pub(crate) fn abort() -> ! {
-    const ROOT_MSG: &str = "Fatal error: Scope dropped while Lifted references still exist. \
-                This would cause undefined behavior. Aborting.\n";
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+
+use crate::lifts::Lifts;
+
+static LEAK_HANDLER: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+static POISONED: AtomicBool = AtomicBool::new(false);
+/// The callbacks of [`register_pre_abort`], in the order they were registered. Fixed slots instead of
+/// a list, so registering needs neither a lock nor an allocation.
+static PRE_ABORT: [AtomicPtr<()>; MAX_PRE_ABORT] =
+    [const { AtomicPtr::new(core::ptr::null_mut()) }; MAX_PRE_ABORT];
+
+/// The maximum number of callbacks that can be registered with [`register_pre_abort`].
+pub const MAX_PRE_ABORT: usize = 8;
+
+/// Sets a handler that is called instead of the default abort when a guard is dropped while
+/// lifted references still exist. e.g. to route the event through custom logging/metrics before
+/// crashing. The handler must never return, since continuing would cause undefined behavior.
+/// [`has_poisoned`] already returns `true` when the handler runs.
+///
+/// Note: If the handler panics instead of aborting, the panic could be recovered from or only
+/// affect the current thread while a lifted reference is in use on another thread.
+pub fn set_leak_handler(handler: fn() -> !) {
+    LEAK_HANDLER.store(handler as *mut (), Ordering::SeqCst);
+}
+
+/// Registers a callback that runs when a guard is dropped while lifted references still exist, right
+/// before the leak handler or the default abort. e.g. to flush logs, write a crash marker or notify a
+/// watchdog. Unlike [`set_leak_handler`], it adds to the default behavior instead of replacing it.
+/// The callbacks run in the order they were registered, and [`has_poisoned`] already returns `true`.
+///
+/// A panic in a callback is caught with `std`, so it cannot prevent the abort, and the next callback
+/// still runs. Returns `f` back if [`MAX_PRE_ABORT`] callbacks are already registered. They cannot
+/// be unregistered.
+pub fn register_pre_abort(f: fn()) -> Result<(), fn()> {
+    for slot in &PRE_ABORT {
+        if slot
+            .compare_exchange(
+                core::ptr::null_mut(),
+                f as *mut (),
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            )
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+    Err(f)
+}
+
+fn run_pre_abort() {
+    for slot in &PRE_ABORT {
+        let f = slot.load(Ordering::SeqCst);
+        if f.is_null() {
+            break;
+        }
+        let f = unsafe { core::mem::transmute::<*mut (), fn()>(f) };
+        #[cfg(feature = "std")]
+        let _ = std::panic::catch_unwind(f);
+        #[cfg(not(feature = "std"))]
+        f();
+    }
+}
+
+/// Whether any guard was dropped while lifted references still existed, in this process. It is set
+/// right before the leak handler runs or the process aborts, so e.g. a leak handler, panic hook or
+/// supervising thread can tell that the crash is due to a leak, and log context before the process
+/// dies. It is never reset.
+pub fn has_poisoned() -> bool {
+    POISONED.load(Ordering::SeqCst)
+}
+
+/// Returned instead of aborting by [`crate::ScopedPinGuard::drop_checked`], when the guard is dropped
+/// while lifted references still exist. Implements [`std::error::Error`] with `std`, so it can be
+/// propagated with `?`.
+///
+/// With the `lift_backtrace` feature it also carries where the lifted references that still exist were
+/// created, which is part of its [`Display`](core::fmt::Display) like in the abort message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeakError {
+    lifted_count: usize,
+    #[cfg(feature = "lift_backtrace")]
+    lifts: String,
+}
+
+impl LeakError {
+    pub(crate) fn new(lifted_count: usize, _lifts: &Lifts) -> Self {
+        LeakError {
+            lifted_count,
+            #[cfg(feature = "lift_backtrace")]
+            lifts: _lifts.to_string(),
+        }
+    }
+
+    /// The number of lifted references that still existed when the guard was dropped.
+    pub fn lifted_count(&self) -> usize {
+        self.lifted_count
+    }
+
+    /// Where the lifted references that still existed were created, with their backtraces.
+    #[cfg(feature = "lift_backtrace")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "lift_backtrace")))]
+    pub fn lifts(&self) -> &str {
+        &self.lifts
+    }
+}
+
+impl core::fmt::Display for LeakError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "guard dropped while {} lifted references still exist",
+            self.lifted_count
+        )?;
+        #[cfg(feature = "lift_backtrace")]
+        f.write_str(&self.lifts)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LeakError {}
+
+/// Formats `value` like a derived `Debug` of a tuple struct named `name`. For the `debug_value` of the
+/// lifted references, whose own `Debug` does not read the value.
+pub(crate) struct DebugValue<'s, T: ?Sized> {
+    pub(crate) name: &'static str,
+    pub(crate) value: &'s T,
+}
+
+impl<T: core::fmt::Debug + ?Sized> core::fmt::Debug for DebugValue<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple(self.name).field(&self.value).finish()
+    }
+}
+
+/// Returned by [`crate::ScopedGuard::try_from_ptr`] instead of creating a guard over a pointer that
+/// cannot be a valid reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewError {
+    /// The pointer is null.
+    Null,
+    /// The pointer is not aligned for the type it points to.
+    Misaligned {
+        /// The address of the pointer.
+        addr: usize,
+        /// The alignment the type requires.
+        align: usize,
+    },
+}
+
+impl core::fmt::Display for NewError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            NewError::Null => f.write_str("cannot lift a null pointer"),
+            NewError::Misaligned { addr, align } => write!(
+                f,
+                "cannot lift the pointer {addr:#x}, which is not aligned to {align} bytes"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NewError {}
+
+/// The start of the message when a guard is dropped while the given number of lifted references
+/// still exist.
+struct LeakMessage(usize);
+
+impl core::fmt::Display for LeakMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let references = if self.0 == 1 {
+            "reference still exists"
+        } else {
+            "references still exist"
+        };
+        writeln!(
+            f,
+            "Fatal error: Scope dropped while {} lifted {references}. This would cause undefined \
+            behavior. Aborting.",
+            self.0
+        )
+    }
+}
+
+pub(crate) fn abort(lifted_count: usize) -> ! {
+    abort_with(lifted_count, &"")
+}
+
+/// Like [`abort`], but appends `details` to the message, e.g. where the lifted references that still
+/// exist were created.
+pub(crate) fn abort_with(lifted_count: usize, details: &dyn core::fmt::Display) -> ! {
+    let root = LeakMessage(lifted_count);
+    POISONED.store(true, Ordering::SeqCst);
+    #[cfg(feature = "audit")]
+    crate::audit::record_leak();
+    run_pre_abort();
+    let handler = LEAK_HANDLER.load(Ordering::SeqCst);
+    if !handler.is_null() {
+        let handler = unsafe { core::mem::transmute::<*mut (), fn() -> !>(handler) };
+        handler();
+    }
     // We don't panic since panics can be recovered and panics also only effect a single thread.
     // While the value could have been sent to a different thread.
-    #[cfg(not(feature = "test"))]
+    #[cfg(all(feature = "std", not(any(feature = "test", feature = "panic_on_leak"))))]
+    abort_process(&root, details);
+    // Without `std` there is no unwinding on stable, so the panic handler cannot return.
+    #[cfg(any(not(feature = "std"), feature = "test", feature = "panic_on_leak"))]
+    {
+        // A panic escaping a drop that runs while unwinding from another panic aborts without
+        // unwinding, so abort with the full message instead of leaving it to the runtime.
+        #[cfg(feature = "std")]
+        if std::thread::panicking() {
+            abort_process(&root, details);
+        }
+        panic!("{root}{details}");
+    }
+}
+
+/// Prints the leak message with a backtrace and aborts the process.
+#[cfg(feature = "std")]
+fn abort_process(root: &LeakMessage, details: &dyn core::fmt::Display) -> ! {
+    let bt = std::backtrace::Backtrace::capture();
+    let msg = match bt.status() {
+        std::backtrace::BacktraceStatus::Unsupported => format!("{root}{details}"),
+        std::backtrace::BacktraceStatus::Disabled => format!(
+            "{root}{details}\n(Hint: re-run with `RUST_BACKTRACE=1` to see a backtrace.)\n"
+        ),
+        std::backtrace::BacktraceStatus::Captured => {
+            format!("{root}{details}\nBacktrace:\n{bt}\n")
+        }
+        _ => format!("{root}{details}"),
+    };
+    use std::io::Write;
+    let _ = std::io::stderr().write_all(msg.as_bytes());
+    let _ = std::io::stderr().flush();
+    std::process::abort();
+}
+
+/// Called when a count of lifted references would overflow, like `Arc` does. A panic would leave the
+/// count above zero, but the increment already happened, so catching the panic in a loop could still
+/// wrap it around.
+#[cold]
+pub(crate) fn abort_overflow() -> ! {
+    const MSG: &str = "Fatal error: Too many lifted references derived from one guard. Aborting.\n";
+    #[cfg(all(feature = "std", not(feature = "test")))]
     {
-        let bt = std::backtrace::Backtrace::capture();
-        let msg = match bt.status() {
-            std::backtrace::BacktraceStatus::Unsupported => ROOT_MSG.to_owned(),
-            std::backtrace::BacktraceStatus::Disabled => {
-                format!("{ROOT_MSG}\n(Hint: re-run with `RUST_BACKTRACE=1` to see a backtrace.)\n")
-            }
-            std::backtrace::BacktraceStatus::Captured => {
-                format!("{ROOT_MSG}\nBacktrace:\n{bt}\n")
-            }
-            _ => ROOT_MSG.to_owned(),
-        };
         use std::io::Write;
-        let _ = std::io::stderr().write_all(msg.as_bytes());
+        let _ = std::io::stderr().write_all(MSG.as_bytes());
         let _ = std::io::stderr().flush();
         std::process::abort();
     }
-    #[cfg(feature = "test")]
+    // Without `std` there is no unwinding on stable, so the panic handler cannot return.
+    #[cfg(any(not(feature = "std"), feature = "test"))]
     {
-        panic!("{}", ROOT_MSG);
+        panic!("{}", MSG);
+    }
+}
+
+/// Exponential backoff used while waiting for lifted references to be dropped. Spins first, then
+/// yields the thread, then sleeps for increasingly longer periods.
+#[cfg(feature = "std")]
+pub(crate) struct Backoff {
+    step: u32,
+}
+
+#[cfg(feature = "std")]
+impl Backoff {
+    const SPIN_LIMIT: u32 = 6;
+    const YIELD_LIMIT: u32 = 10;
+    const SLEEP_LIMIT: u32 = 20;
+
+    pub(crate) fn new() -> Self {
+        Backoff { step: 0 }
+    }
+
+    pub(crate) fn snooze(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            for _ in 0..1 << self.step {
+                std::hint::spin_loop();
+            }
+        } else if cfg!(feature = "shuttle") {
+            // The threads of shuttle only make progress when they yield to its scheduler
+            #[cfg(feature = "shuttle")]
+            shuttle::thread::yield_now();
+        } else if self.step <= Self::YIELD_LIMIT {
+            std::thread::yield_now();
+        } else {
+            let micros = 1u64 << (self.step - Self::YIELD_LIMIT);
+            std::thread::sleep(std::time::Duration::from_micros(micros));
+        }
+        if self.step < Self::SLEEP_LIMIT {
+            self.step += 1;
+        }
+    }
+}
+
+/// Held by the tests that set a leak handler or depend on the default leak message, which a set
+/// handler replaces. Shared by the tests of all modules, since the handler is global.
+#[cfg(test)]
+pub(crate) static HANDLER_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use super::{HANDLER_LOCK, LeakError, has_poisoned, register_pre_abort, set_leak_handler};
+    use crate::lifts::Lifts;
+    use crate::{ScopedGuard, ScopedPinGuard};
+
+    const HANDLER_MSG: &str = "custom leak handler";
+
+    fn panicking_handler() -> ! {
+        panic!("{}", HANDLER_MSG);
+    }
+
+    #[test]
+    fn leak_handler() {
+        let _lock = HANDLER_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Other tests that leak may also reach this handler, which panics like the `test` feature does.
+        set_leak_handler(panicking_handler);
+        let concrete_value = Box::new(1.0);
+        let ref_value = &concrete_value;
+        let guard = unsafe { ScopedGuard::new(ref_value) };
+        let lifted = guard.lift();
+        let result = std::panic::catch_unwind(|| {
+            std::mem::drop(guard);
+        });
+        std::mem::drop(lifted);
+        super::LEAK_HANDLER.store(std::ptr::null_mut(), std::sync::atomic::Ordering::SeqCst);
+        let payload = result.expect_err("expected the leak handler to run");
+        let msg = payload
+            .downcast_ref::<String>()
+            .expect("expected a formatted panic message");
+        assert_eq!(msg, HANDLER_MSG);
+    }
+
+    fn leak_message(leak: impl FnOnce()) -> String {
+        let payload = std::panic::catch_unwind(std::panic::AssertUnwindSafe(leak))
+            .expect_err("expected dropping a guard with a lifted reference to panic");
+        payload
+            .downcast_ref::<String>()
+            .expect("expected a formatted panic message")
+            .clone()
+    }
+
+    static PRE_ABORT_RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+    fn panicking_pre_abort() {
+        panic!("pre-abort callback panicked");
+    }
+
+    fn counting_pre_abort() {
+        assert!(has_poisoned());
+        PRE_ABORT_RUNS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+    }
+
+    #[test]
+    fn pre_abort() {
+        let _lock = HANDLER_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        // The callbacks stay registered, so other tests that leak also run them
+        register_pre_abort(panicking_pre_abort).unwrap();
+        register_pre_abort(counting_pre_abort).unwrap();
+        let before = PRE_ABORT_RUNS.load(std::sync::atomic::Ordering::SeqCst);
+        let concrete_value = Box::new(1.0);
+        let guard = unsafe { ScopedGuard::new(&concrete_value) };
+        let lifted = guard.lift();
+        // Still panics like the default abort does with the `test` feature
+        leak_message(|| std::mem::drop(guard));
+        std::mem::drop(lifted);
+        assert!(PRE_ABORT_RUNS.load(std::sync::atomic::Ordering::SeqCst) > before);
+    }
+
+    fn poisoned_handler() -> ! {
+        panic!("poisoned: {}", has_poisoned());
+    }
+
+    #[test]
+    fn poisoned() {
+        let _lock = HANDLER_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Sees the flag like a leak handler that reports the crash would
+        set_leak_handler(poisoned_handler);
+        let concrete_value = Box::new(1.0);
+        let guard = unsafe { ScopedGuard::new(&concrete_value) };
+        let lifted = guard.lift();
+        let msg = leak_message(|| std::mem::drop(guard));
+        std::mem::drop(lifted);
+        super::LEAK_HANDLER.store(std::ptr::null_mut(), std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(msg, "poisoned: true");
+        assert!(has_poisoned());
+    }
+
+    #[test]
+    fn leak_message_has_count() {
+        let _lock = HANDLER_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let concrete_value = Box::new(1.0);
+        let ref_value = &concrete_value;
+
+        let guard = unsafe { ScopedGuard::new(ref_value) };
+        let lifted: Vec<_> = (0..3).map(|_| guard.lift()).collect();
+        let msg = leak_message(|| std::mem::drop(guard));
+        std::mem::drop(lifted);
+        assert!(msg.contains("while 3 lifted references still exist"), "{msg}");
+
+        let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+        let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+        std::mem::forget(guard.lift());
+        let msg = leak_message(|| std::mem::drop(guard_unpinned));
+        assert!(msg.contains("while 1 lifted reference still exists"), "{msg}");
+    }
+
+    // With `lift_backtrace` the messages differ by where the references were lifted
+    #[cfg(not(feature = "lift_backtrace"))]
+    #[test]
+    fn guards_share_leak_path() {
+        let _lock = HANDLER_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let concrete_value = Box::new(1.0);
+        let ref_value = &concrete_value;
+
+        let guard = unsafe { ScopedGuard::new(ref_value) };
+        let lifted = guard.lift();
+        let scoped_msg = leak_message(|| std::mem::drop(guard));
+        std::mem::drop(lifted);
+
+        let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+        let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+        let lifted = guard.lift();
+        // Keep the count non-zero without a `ScopedPin` outliving the guard
+        std::mem::forget(lifted);
+        let pin_msg = leak_message(|| std::mem::drop(guard_unpinned));
+
+        assert_eq!(scoped_msg, pin_msg);
+    }
+
+    #[cfg(feature = "lift_backtrace")]
+    #[test]
+    fn leak_message_has_lift_location() {
+        let _lock = HANDLER_LOCK.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let concrete_value = Box::new(1.0);
+        let ref_value = &concrete_value;
+
+        let guard = unsafe { ScopedGuard::new(ref_value) };
+        let lifted = guard.lift();
+        let lifted_line = line!() - 1;
+        let released = guard.lift();
+        drop(released);
+        let msg = leak_message(|| std::mem::drop(guard));
+        std::mem::drop(lifted);
+        assert!(msg.contains(&format!("{}:{lifted_line}:", file!())), "{msg}");
+        assert_eq!(msg.matches("Lifted reference created at").count(), 1, "{msg}");
+
+        let guard_unpinned = unsafe { ScopedPinGuard::new(ref_value) };
+        let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+        let lifted = guard.lift();
+        let cloned = lifted.clone();
+        let cloned_line = line!() - 1;
+        drop(lifted);
+        std::mem::forget(cloned);
+        let msg = leak_message(|| std::mem::drop(guard_unpinned));
+        assert!(msg.contains(&format!("{}:{cloned_line}:", file!())), "{msg}");
+        assert_eq!(msg.matches("Lifted reference created at").count(), 1, "{msg}");
+    }
+
+    #[test]
+    fn leak_error() {
+        fn check(lifted_count: usize) -> Result<(), Box<dyn std::error::Error>> {
+            if lifted_count != 0 {
+                Err(LeakError::new(lifted_count, &Lifts::new()))?;
+            }
+            Ok(())
+        }
+        assert!(check(0).is_ok());
+        let error = check(3).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "guard dropped while 3 lifted references still exist"
+        );
+        let error = error.downcast::<LeakError>().unwrap();
+        assert_eq!(error.lifted_count(), 3);
+    }
+
+    #[cfg(feature = "lift_backtrace")]
+    #[test]
+    fn leak_error_has_lift_location() {
+        let concrete_value = Box::new(1.0);
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&concrete_value) };
+        let guard = unsafe { std::pin::Pin::new_unchecked(&guard_unpinned) };
+        let lifted = guard.lift();
+        let lifted_line = line!() - 1;
+        let error = unsafe { guard_unpinned.drop_checked() }.unwrap_err();
+        std::mem::forget(lifted);
+        assert!(error.lifts().contains(&format!("{}:{lifted_line}:", file!())), "{error}");
+        assert!(error.to_string().ends_with(error.lifts()));
     }
 }
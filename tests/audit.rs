@@ -0,0 +1,57 @@
+//! Checks the registry of the `audit` feature. The registry is global to the process, so everything
+//! runs in one test, in this binary of its own.
+#![cfg(feature = "audit")]
+
+use std::pin::Pin;
+
+#[cfg(any(feature = "test", feature = "panic_on_leak"))]
+use scoped_static::leaked_scopes;
+use scoped_static::{
+    ScopedGuard, ScopedPinGuard, assert_no_outstanding_scopes, outstanding_scopes,
+};
+
+#[test]
+fn audit() {
+    let value = Box::new(1.0);
+
+    // A clean run
+    {
+        let guard = unsafe { ScopedGuard::new(&*value) };
+        let lifted = guard.lift();
+        let cloned = lifted.clone();
+        assert_eq!(outstanding_scopes(), 1);
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&*value) };
+        let pin_guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        let pin_lifted = pin_guard.lift();
+        assert_eq!(outstanding_scopes(), 2);
+        std::thread::spawn(move || assert_eq!(*lifted + *cloned + *pin_lifted, 3.0))
+            .join()
+            .unwrap();
+        // Never lifted, so never counted
+        let _unused = unsafe { ScopedGuard::new(&*value) };
+    }
+    assert_eq!(outstanding_scopes(), 0);
+    assert_no_outstanding_scopes();
+
+    // A forgotten guard is never checked by its drop, but is still outstanding
+    let guard = unsafe { ScopedGuard::new(&*value) };
+    std::mem::drop(guard.lift());
+    std::mem::forget(guard);
+    assert_eq!(outstanding_scopes(), 1);
+    assert!(std::panic::catch_unwind(assert_no_outstanding_scopes).is_err());
+
+    // A leaking run, where the leak panics instead of aborting
+    #[cfg(any(feature = "test", feature = "panic_on_leak"))]
+    {
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&*value) };
+        let pin_guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        std::mem::forget(pin_guard.lift());
+        let leaked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            std::mem::drop(guard_unpinned)
+        }));
+        assert!(leaked.is_err());
+        assert_eq!(leaked_scopes(), 1);
+        // The pinned guard itself is gone
+        assert_eq!(outstanding_scopes(), 1);
+    }
+}
@@ -0,0 +1,114 @@
+//! Checks that the pinned handles stay zero-cost, by compiling the wrappers in `tests/codegen` against
+//! a release build of the crate with the default features and inspecting the x86_64 assembly.
+//!
+//! The expected codegen, with everything inlined into the wrappers:
+//! - `ScopedPinGuard::lift` and `ScopedPin::clone` are a single `lock inc` and a branch to the cold
+//!   `abort_overflow` if the count went past `isize::MAX`.
+//! - Dropping a `ScopedPin` is a single `lock dec`, without any branch.
+//!
+//! Print the assembly when a check fails to see what changed.
+//!
+//! Ignored by default, since it builds the crate again. Run it with
+//! `cargo test --test codegen -- --ignored`.
+
+#![cfg(target_arch = "x86_64")]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The `rustc` of the toolchain of the `cargo` that builds the crate, instead of whichever `rustc` is
+/// first on `PATH`, so the wrappers link against an rlib of the same compiler.
+fn rustc() -> PathBuf {
+    Path::new(env!("CARGO")).with_file_name(format!("rustc{}", std::env::consts::EXE_SUFFIX))
+}
+
+fn assembly() -> String {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let target_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    let status = Command::new(env!("CARGO"))
+        .current_dir(manifest_dir)
+        .args(["build", "--release", "--lib", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to build the crate");
+    let output = target_dir.join("wrappers.s");
+    let status = Command::new(rustc())
+        .args([
+            "--edition",
+            "2024",
+            "--crate-type",
+            "lib",
+            "--crate-name",
+            "wrappers",
+        ])
+        .args([
+            "-C",
+            "opt-level=3",
+            "-C",
+            "codegen-units=1",
+            "--emit",
+            "asm",
+        ])
+        .arg("--extern")
+        .arg(format!(
+            "scoped_static={}",
+            target_dir.join("release/libscoped_static.rlib").display()
+        ))
+        .arg("-o")
+        .arg(&output)
+        .arg(manifest_dir.join("tests/codegen/wrappers.rs"))
+        .status()
+        .unwrap();
+    assert!(status.success(), "failed to compile the wrappers");
+    std::fs::read_to_string(output).unwrap()
+}
+
+/// The instructions of the function `name`, without the directives and labels.
+fn instructions<'a>(assembly: &'a str, name: &str) -> Vec<&'a str> {
+    let start = format!("{name}:");
+    let body: Vec<&str> = assembly
+        .lines()
+        .skip_while(|line| *line != start)
+        .skip(1)
+        .take_while(|line| !line.contains(".cfi_endproc"))
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('.') && !line.ends_with(':'))
+        .collect();
+    assert!(!body.is_empty(), "`{name}` not found in:\n{assembly}");
+    body
+}
+
+fn count(instructions: &[&str], mnemonic: &str) -> usize {
+    instructions
+        .iter()
+        .filter(|instruction| instruction.starts_with(mnemonic))
+        .count()
+}
+
+/// A single atomic increment, and no calls except to abort on overflow.
+fn assert_increment(assembly: &str, name: &str) {
+    let body = instructions(assembly, name);
+    assert_eq!(count(&body, "lock"), 1, "`{name}`:\n{}", body.join("\n"));
+    assert!(
+        body.iter()
+            .filter(|instruction| instruction.starts_with("call"))
+            .all(|call| call.contains("abort_overflow")),
+        "`{name}`:\n{}",
+        body.join("\n")
+    );
+    assert!(count(&body, "j") <= 1, "`{name}`:\n{}", body.join("\n"));
+}
+
+#[test]
+#[ignore = "builds the crate again, run in the codegen CI job"]
+fn zero_cost() {
+    let assembly = assembly();
+    assert_increment(&assembly, "scoped_pin_lift");
+    assert_increment(&assembly, "scoped_pin_clone");
+
+    let drop = instructions(&assembly, "scoped_pin_drop");
+    assert_eq!(count(&drop, "lock"), 1, "{}", drop.join("\n"));
+    assert_eq!(count(&drop, "call"), 0, "{}", drop.join("\n"));
+    assert_eq!(count(&drop, "j"), 0, "{}", drop.join("\n"));
+}
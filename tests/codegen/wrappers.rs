@@ -0,0 +1,21 @@
+//! Non-generic wrappers around the hot paths of `ScopedPin`, compiled by `tests/codegen.rs` to check
+//! the generated assembly.
+
+use core::pin::Pin;
+
+use scoped_static::{ScopedPin, ScopedPinGuard};
+
+#[unsafe(no_mangle)]
+pub fn scoped_pin_lift(guard: Pin<&ScopedPinGuard<'_, u64>>) -> ScopedPin<u64> {
+    guard.lift()
+}
+
+#[unsafe(no_mangle)]
+pub fn scoped_pin_drop(lifted: ScopedPin<u64>) {
+    drop(lifted)
+}
+
+#[unsafe(no_mangle)]
+pub fn scoped_pin_clone(lifted: &ScopedPin<u64>) -> ScopedPin<u64> {
+    lifted.clone()
+}
@@ -0,0 +1,9 @@
+//! Locks in the compile time guarantees that the runtime checks rely on.
+//! Regenerate the expected errors with `TRYBUILD=overwrite cargo test --features test --test compile_fail`.
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
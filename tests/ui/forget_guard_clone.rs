@@ -0,0 +1,14 @@
+use scoped_static::scoped;
+
+fn forget_clone<T: Clone>(value: &T) {
+    std::mem::forget(value.clone());
+}
+
+fn main() {
+    let value = Box::new(1.0);
+    let guard = scoped!(&value);
+    let lifted = guard.lift();
+    // A leaked clone would keep the borrow from being checked when `guard` is dropped
+    forget_clone(&*guard);
+    assert_eq!(**lifted, 1.0);
+}
@@ -0,0 +1,8 @@
+use scoped_static::scoped_pin_local;
+
+fn main() {
+    let value = 1.0;
+    scoped_pin_local!(guard, &value);
+    let lifted = guard.lift();
+    std::thread::spawn(move || assert_eq!(*lifted, 1.0));
+}
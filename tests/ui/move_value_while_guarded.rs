@@ -0,0 +1,10 @@
+use scoped_static::scoped;
+
+fn main() {
+    let value = Box::new(1.0);
+    let guard = scoped!(&value);
+    // The guard borrows `value` for `'a`, so it can't be moved out while the guard exists
+    std::mem::drop(value);
+    let lifted = guard.lift();
+    assert_eq!(**lifted, 1.0);
+}
@@ -0,0 +1,10 @@
+use scoped_static::scoped;
+
+fn main() {
+    let value = 1.0;
+    let inner = &value;
+    // Only the outer reference is lifted, `inner` itself would still dangle
+    let guard = scoped!(&inner);
+    let lifted = guard.lift();
+    std::thread::spawn(move || assert_eq!(**lifted, 1.0));
+}
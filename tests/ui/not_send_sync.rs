@@ -0,0 +1,23 @@
+use std::cell::Cell;
+
+use scoped_static::{Scoped, ScopedGuard, ScopedLocal, ScopedPin, ScopedPinGuard, ScopedPinGuardLocal};
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+fn main() {
+    // Like `&T`, the guards and lifted references must not be shared across threads unless `T: Sync`
+    assert_send::<ScopedGuard<'static, Cell<i32>>>();
+    assert_sync::<ScopedGuard<'static, Cell<i32>>>();
+    assert_send::<Scoped<Cell<i32>>>();
+    assert_sync::<Scoped<Cell<i32>>>();
+    assert_send::<ScopedPinGuard<'static, Cell<i32>>>();
+    assert_sync::<ScopedPinGuard<'static, Cell<i32>>>();
+    assert_send::<ScopedPin<Cell<i32>>>();
+    assert_sync::<ScopedPin<Cell<i32>>>();
+    // The local counts are not atomic, so these never leave their thread
+    assert_send::<ScopedPinGuardLocal<'static, f64>>();
+    assert_sync::<ScopedPinGuardLocal<'static, f64>>();
+    assert_send::<ScopedLocal<f64>>();
+    assert_sync::<ScopedLocal<f64>>();
+}
@@ -0,0 +1,9 @@
+use scoped_static::ScopedPinGuard;
+
+fn main() {
+    let value = 1.0;
+    let guard = unsafe { ScopedPinGuard::new(&value) };
+    // Lifting requires the guard to be pinned first
+    let lifted = guard.lift();
+    assert_eq!(*lifted, 1.0);
+}
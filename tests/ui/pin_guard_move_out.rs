@@ -0,0 +1,11 @@
+use scoped_static::scoped_pin;
+
+fn main() {
+    let value = 1.0;
+    scoped_pin!(guard, &value);
+    let lifted = guard.lift();
+    // The macro shadows the guard with its pinned reference, so it can't be moved out anymore
+    let moved = std::mem::replace(&mut *guard, unsafe { scoped_static::ScopedPinGuard::new(&value) });
+    std::mem::drop(moved);
+    assert_eq!(*lifted, 1.0);
+}
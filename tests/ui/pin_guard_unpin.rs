@@ -0,0 +1,15 @@
+use scoped_static::{
+    ScopedPinGuard, ScopedPinGuardLocal, ScopedPinGuardMulti, ScopedPinGuardOwned,
+    ScopedPinGuardPinned,
+};
+
+fn assert_unpin<T: Unpin>() {}
+
+fn main() {
+    // The pinned guards must never be movable once lifted from
+    assert_unpin::<ScopedPinGuard<'static, f64>>();
+    assert_unpin::<ScopedPinGuardMulti<'static>>();
+    assert_unpin::<ScopedPinGuardLocal<'static, f64>>();
+    assert_unpin::<ScopedPinGuardOwned<f64>>();
+    assert_unpin::<ScopedPinGuardPinned<'static, f64>>();
+}
@@ -0,0 +1,12 @@
+use scoped_static::scoped_pin_multi;
+
+fn main() {
+    scoped_pin_multi!(g);
+    let l;
+    {
+        let s = String::new();
+        // The guard outlives `s`, so it cannot lift it
+        l = g.lift(&s);
+    }
+    std::thread::spawn(move || l.len());
+}
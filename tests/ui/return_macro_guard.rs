@@ -0,0 +1,11 @@
+use scoped_static::{ScopedGuard, scoped};
+
+// The `scoped!` guard is a reference to a temporary, so it can't escape the scope that drops it
+fn escape(value: &f64) -> &mut ScopedGuard<'_, f64> {
+    scoped!(value)
+}
+
+fn main() {
+    let value = 1.0;
+    let _ = escape(&value);
+}
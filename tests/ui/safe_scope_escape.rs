@@ -0,0 +1,12 @@
+use scoped_static::SafeScope;
+
+fn main() {
+    let value = 1.0;
+    let mut escaped = None;
+    SafeScope::with(&value, |s| {
+        // The scope can't outlive the closure, and the guard it owns can't be moved out to leak it
+        escaped = Some(s);
+    });
+    let lifted = escaped.unwrap().lift();
+    assert_eq!(*lifted, 1.0);
+}
@@ -0,0 +1,10 @@
+fn main() {
+    let value = 1.0;
+    let mut escaped = None;
+    scoped_static::scope(|s| {
+        // The scope handle can't outlive the closure
+        escaped = Some(s);
+    });
+    let lifted = escaped.unwrap().lift(&value);
+    assert_eq!(*lifted, 1.0);
+}
@@ -0,0 +1,13 @@
+use scoped_static::{ScopeToken, ScopedPinGuard};
+
+fn main() {
+    let value = 1.0;
+    let escaped = {
+        let token = ScopeToken::new();
+        // The value outlives the block, but the guard can't outlive the token
+        unsafe { ScopedPinGuard::new_with(&value, &token) }
+    };
+    let guard = std::pin::pin!(escaped);
+    let lifted = guard.as_ref().lift();
+    assert_eq!(*lifted, 1.0);
+}
@@ -0,0 +1,18 @@
+use scoped_static::{scoped_mut, ScopedMut};
+
+fn keep(value: &'static str) {
+    println!("{value}");
+}
+
+fn main() {
+    let mut f: for<'a> fn(&'a str) = |_| {};
+    {
+        let guard = scoped_mut!(&mut f);
+        let lifted = guard.lift_mut().unwrap();
+        // Writing through a `ScopedMut` of a supertype would store a function that needs `'static`
+        let mut shortened: ScopedMut<fn(&'static str)> = lifted;
+        *shortened = keep;
+    }
+    let value = String::from("value");
+    f(&value);
+}
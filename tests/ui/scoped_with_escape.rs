@@ -0,0 +1,28 @@
+use std::cell::Cell;
+
+use scoped_static::{Family, ScopedWithGuard};
+
+struct Foo<'b> {
+    current: Cell<&'b str>,
+}
+
+struct FooFamily;
+
+impl Family for FooFamily {
+    type Of<'b> = Foo<'b>;
+}
+
+fn main() {
+    let name = String::from("one");
+    let foo = Foo {
+        current: Cell::new(&name),
+    };
+    let guard = unsafe { ScopedWithGuard::<FooFamily>::new(&foo) };
+    let lifted = guard.lift();
+    let escaped: Cell<&'static str> = Cell::new("");
+    // The value can be used with any lifetime, so its borrows can't be copied out, nor can a shorter
+    // borrow be stored into it
+    lifted.with(|foo| escaped.set(foo.current.get()));
+    let local = String::from("two");
+    lifted.with(|foo| foo.current.set(&local));
+}
@@ -0,0 +1,14 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use scoped_static::{Scoped, ScopedPin, assert_spawn_safe};
+
+fn check(scoped: Scoped<Cell<i32>>, pin: ScopedPin<Cell<i32>>, rc: Rc<i32>) {
+    // A lifted reference is only `Send` if the value is `Sync`, like `&T`
+    assert_spawn_safe(&scoped);
+    assert_spawn_safe(&pin);
+    // Not a lifted reference at all
+    assert_spawn_safe(&rc);
+}
+
+fn main() {}
@@ -0,0 +1,64 @@
+//! Checks that dropping a guard while lifted references still exist aborts the process even while
+//! unwinding from another panic, also with the `panic_on_leak` or `test` features, where a leak
+//! otherwise panics. Each case runs in a child process of this test binary, since it aborts.
+
+use std::pin::Pin;
+use std::process::Command;
+
+use scoped_static::{ScopedGuard, ScopedPinGuard};
+
+/// Set for the child process, to the name of the case to run.
+const CASE: &str = "SCOPED_STATIC_UNWIND_CASE";
+
+/// Runs `case` in a child process if this is the parent, and checks that it aborted with the leak
+/// message.
+fn assert_aborts(name: &str, case: fn()) {
+    if std::env::var(CASE).is_ok_and(|value| value == name) {
+        case();
+        unreachable!("the case returned");
+    }
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args([name, "--exact", "--nocapture", "--test-threads=1"])
+        .env(CASE, name)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "{stderr}");
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        // SIGABRT
+        assert_eq!(output.status.signal(), Some(6), "{stderr}");
+    }
+    assert!(stderr.contains("unrelated panic"), "{stderr}");
+    assert!(
+        stderr.contains("Scope dropped while 1 lifted reference still exists"),
+        "{stderr}"
+    );
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn scoped_guard_dropped_while_unwinding() {
+    assert_aborts("scoped_guard_dropped_while_unwinding", || {
+        let value = 1.0;
+        let _lifted;
+        let guard = unsafe { ScopedGuard::new(&value) };
+        // The guard is dropped before `_lifted` while unwinding
+        _lifted = guard.lift();
+        panic!("unrelated panic");
+    });
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn scoped_pin_guard_dropped_while_unwinding() {
+    assert_aborts("scoped_pin_guard_dropped_while_unwinding", || {
+        let value = 1.0;
+        let _lifted;
+        let guard_unpinned = unsafe { ScopedPinGuard::new(&value) };
+        let guard = unsafe { Pin::new_unchecked(&guard_unpinned) };
+        _lifted = guard.lift();
+        panic!("unrelated panic");
+    });
+}